@@ -14,6 +14,7 @@ use scion::core::resources::audio::PlayConfig;
 use scion::core::resources::inputs::types::{KeyCode};
 use scion::core::resources::inputs::types::Input::Key;
 use scion::core::scene::Scene;
+use scion::core::scene_transition::Transition;
 use scion::core::world::{GameData, World};
 use scion::utils::file::app_base_path_join;
 
@@ -89,9 +90,12 @@ impl Scene for Menu {
 
     fn on_update(&mut self, data: &mut GameData) {
         self.animate_main_menu_ship(data);
-        if data.inputs().input_pressed(&Key(KeyCode::Enter)) {
+        if data.inputs().input_pressed_event(&Key(KeyCode::Enter)) {
             data.game_state_mut().set_text(CURRENT_LEVEL, "lvl1");
-            data.scene_controller().switch::<LevelScene>();
+            data.scene_controller().switch_with_transition::<LevelScene>(Transition::FadeToColor {
+                color: Color::new_rgb(0, 0, 0),
+                duration: Duration::from_millis(600),
+            });
         };
     }
 
@@ -9,13 +9,7 @@ use scion::{
     Scion,
 };
 
-use crate::{
-    scene::MainScene,
-    systems::{
-        move_system::move_piece_system, piece_system::piece_update_system,
-        rotation_system::piece_rotation_system, score_system::score_system,
-    },
-};
+use crate::scene::MainScene;
 
 mod components;
 pub mod resources;
@@ -25,10 +19,6 @@ mod systems;
 fn main() {
     Scion::app_with_config(app_config())
         .with_scene::<MainScene>()
-        .with_pausable_system(piece_update_system, |gs| gs.get_bool("pause"))
-        .with_pausable_system(move_piece_system, |gs| gs.get_bool("pause"))
-        .with_pausable_system(piece_rotation_system, |gs| gs.get_bool("pause"))
-        .with_system(score_system)
         .run();
 }
 
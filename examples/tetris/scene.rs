@@ -7,7 +7,7 @@ use scion::{
         ui::{font::Font, ui_image::UiImage, ui_text::UiText},
     },
     core::resources::time::TimerType,
-    core::scene::Scene,
+    core::scene::{Scene, SceneSystems},
 };
 use scion::graphics::components::color::Color;
 use scion::graphics::components::material::Material;
@@ -17,7 +17,14 @@ use scion::graphics::components::ui::ui_button::UiButton;
 use scion::graphics::components::ui::ui_input::UiInput;
 use scion::core::resources::inputs::types::{Input, KeyCode};
 
-use crate::{asset_path, resources::TetrisResource};
+use crate::{
+    asset_path,
+    resources::TetrisResource,
+    systems::{
+        move_system::move_piece_system, piece_system::piece_update_system,
+        rotation_system::piece_rotation_system, score_system::score_system,
+    },
+};
 
 #[derive(Default)]
 pub struct MainScene {
@@ -25,6 +32,13 @@ pub struct MainScene {
 }
 
 impl Scene for MainScene {
+    fn register_systems(&self, systems: &mut SceneSystems) {
+        systems.add_pausable_system(piece_update_system, |gs| gs.get_bool("pause"));
+        systems.add_pausable_system(move_piece_system, |gs| gs.get_bool("pause"));
+        systems.add_pausable_system(piece_rotation_system, |gs| gs.get_bool("pause"));
+        systems.add_system(score_system);
+    }
+
     fn on_start(&mut self, data: &mut GameData) {
         add_main_ui_mask(data);
         add_ui_top_overflow(data);
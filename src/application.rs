@@ -8,7 +8,7 @@ use std::time::Duration;
 use log::{info};
 use winit::{
     event::{Event, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
+    event_loop::{ControlFlow, EventLoopBuilder},
     window::{WindowBuilder},
 };
 
@@ -17,6 +17,7 @@ use crate::{
     config::scion_config::{ScionConfig, ScionConfigReader},
 };
 use crate::core::application_builder::ScionBuilder;
+use crate::core::resources::audio::Audio;
 
 
 use crate::core::scene::{SceneMachine};
@@ -26,7 +27,7 @@ use crate::core::scion_runner::ScionRunner;
 
 use crate::core::world::GameData;
 use crate::graphics::rendering::scion2d::window_rendering_manager::ScionWindowRenderingManager;
-use crate::graphics::windowing::WindowingEvent;
+use crate::graphics::windowing::{WindowCommand, WindowingEvent};
 
 /// `Scion` is the entry point of any application made with Scion's lib.
 pub struct Scion {
@@ -68,10 +69,11 @@ impl Scion {
 
     // There was no technical need to have the run function inside the Scion struct, but I made it here because I wanted the
     // main window loop & game loop to be in the main application file.
-    pub(crate) fn run(self) {
+    pub(crate) fn run(mut self) {
         if self.config.window_config.is_none() {
-            // Running window less mode, so launching the runner in the main thread
-            info!("Launching game in text mode");
+            // Running headless, without window, gpu or audio device, so launching the runner in the main thread
+            info!("Launching game in headless mode");
+            self.game_data.insert_resource(Audio::disabled());
             ScionRunner {
                 game_data: self.game_data,
                 scheduler: self.scheduler,
@@ -79,12 +81,16 @@ impl Scion {
                 window_rendering_manager: None,
                 window: None,
                 main_thread_receiver: None,
+                main_thread_proxy: None,
                 scion_pre_renderer: Default::default(),
+                frame_limiter_config: self.config.frame_limiter_config.clone(),
             }.launch_game_loop();
         } else {
             // Game is running in a window, it must be created & handled in the main thread, so
             // the game loop is going to another thread.
-            let event_loop = EventLoop::new().expect("Event loop could not be created");
+            let event_loop = EventLoopBuilder::<WindowCommand>::with_user_event()
+                .build()
+                .expect("Event loop could not be created");
             event_loop.set_control_flow(ControlFlow::Wait);
             let window_builder: WindowBuilder = self.config.window_config
                 .clone()
@@ -93,9 +99,15 @@ impl Scion {
             let window = Arc::new(window_builder
                 .build(&event_loop)
                 .expect("An error occured while building the main game window"));
-            let window_rendering_manager = futures::executor::block_on(ScionWindowRenderingManager::new(window.clone(), self.config.window_config.as_ref().unwrap().default_background_color.clone()));
+            let window_rendering_manager = futures::executor::block_on(ScionWindowRenderingManager::new(
+                window.clone(),
+                self.config.window_config.as_ref().unwrap().default_background_color.clone(),
+                self.config.frame_limiter_config.present_mode,
+            ));
+            let frame_limiter_config = self.config.frame_limiter_config.clone();
             let (event_sender, receiver) = mpsc::channel::<WindowingEvent>();
-            thread::spawn(move || {
+            let main_thread_proxy = event_loop.create_proxy();
+            let game_loop = thread::spawn(move || {
                 ScionRunner {
                     game_data: self.game_data,
                     scheduler: self.scheduler,
@@ -103,14 +115,21 @@ impl Scion {
                     window_rendering_manager: Some(window_rendering_manager),
                     window: Some(window.clone()),
                     main_thread_receiver: Some(receiver),
+                    main_thread_proxy: Some(main_thread_proxy),
                     scion_pre_renderer: Default::default(),
+                    frame_limiter_config,
                 }.launch_game_loop();
             });
             let _result = event_loop.run(move |event, loopd| {
                 match event {
                     Event::WindowEvent { event, window_id: _ } => {
                         match event {
-                            WindowEvent::CloseRequested => loopd.exit(),
+                            WindowEvent::CloseRequested => {
+                                // The game loop decides whether to quit, unless it is not running anymore
+                                if event_sender.send(WindowingEvent { window_event: Some(WindowEvent::CloseRequested), redraw: false }).is_err() {
+                                    loopd.exit();
+                                }
+                            }
                             WindowEvent::RedrawRequested => {
                                 let _r = event_sender.send(WindowingEvent { window_event: Some(WindowEvent::RedrawRequested), redraw: true });
                             }
@@ -119,12 +138,15 @@ impl Scion {
                             }
                         }
                     }
+                    Event::UserEvent(WindowCommand::Exit) => loopd.exit(),
                     Event::AboutToWait => {
                         //
                     }
                     _ => {}
                 }
             });
+            let _r = game_loop.join();
         }
+        info!("Scion app has been shut down");
     }
 }
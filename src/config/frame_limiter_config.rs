@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+/// In order to reduce the cpu usage, the `FrameLimiter` will handle an
+/// ecs Lock if a frame used less time than expected.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum FrameLimiterStrategy {
+    /// The `FrameLimiter` won't try to sleep and will launch the next ecs frame
+    /// immediately after the previous one. Updates and renders happen at each loop iteration,
+    /// only the fixed updates keep their rate.
+    Unlimited,
+    /// The `FrameLimiter` will compute the expected duration of a frame and
+    /// do a background sleep, locking the ecs, but not the rendering or window events.
+    Sleep,
+}
+
+/// `PresentMode` tells how the rendered frames are presented to the window, and so if the vsync is used.
+/// If the selected mode is not supported by the surface, `AutoVsync` is used.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum PresentMode {
+    /// Vsync if available, falling back to `Fifo`
+    AutoVsync,
+    /// No vsync if available, falling back to `Fifo`
+    AutoNoVsync,
+    /// Vsync, frames are queued and presented on each vertical blank
+    Fifo,
+    /// No vsync, frames are presented immediately, tearing can be visible
+    Immediate,
+    /// Vsync, only the latest frame is presented on each vertical blank
+    Mailbox,
+}
+
+impl From<PresentMode> for wgpu::PresentMode {
+    fn from(mode: PresentMode) -> Self {
+        match mode {
+            PresentMode::AutoVsync => wgpu::PresentMode::AutoVsync,
+            PresentMode::AutoNoVsync => wgpu::PresentMode::AutoNoVsync,
+            PresentMode::Fifo => wgpu::PresentMode::Fifo,
+            PresentMode::Immediate => wgpu::PresentMode::Immediate,
+            PresentMode::Mailbox => wgpu::PresentMode::Mailbox,
+        }
+    }
+}
+
+/// Configuration of the game loop rates.
+/// Please use [`FrameLimiterConfigBuilder`] if you want to build if from code.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrameLimiterConfig {
+    /// Strategy used to limit the game loop
+    pub(crate) strategy: FrameLimiterStrategy,
+    /// Target number of updates (scene updates and systems) per second
+    pub(crate) update_rate: u32,
+    /// Number of fixed updates per second
+    pub(crate) fixed_update_rate: u32,
+    /// Maximum number of fixed updates executed in a single frame. When the game loop is late by more
+    /// than this number of fixed steps, the remaining late time is dropped instead of being caught up.
+    pub(crate) max_fixed_updates_per_frame: u32,
+    /// Target number of renders per second
+    pub(crate) render_rate: u32,
+    /// Present mode forwarded to the window surface
+    pub(crate) present_mode: PresentMode,
+}
+
+impl Default for FrameLimiterConfig {
+    fn default() -> Self {
+        Self {
+            strategy: FrameLimiterStrategy::Sleep,
+            update_rate: 60,
+            fixed_update_rate: 60,
+            max_fixed_updates_per_frame: 5,
+            render_rate: 60,
+            present_mode: PresentMode::AutoVsync,
+        }
+    }
+}
+
+/// `FrameLimiterConfigBuilder` is a convenience builder to create a `FrameLimiterConfig` from code.
+pub struct FrameLimiterConfigBuilder {
+    config: FrameLimiterConfig,
+}
+
+impl Default for FrameLimiterConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameLimiterConfigBuilder {
+    /// Create a new `FrameLimiterConfigBuilder` builder
+    pub fn new() -> Self {
+        Self { config: Default::default() }
+    }
+
+    /// Removes any frame limitation : updates and renders will happen as fast as possible
+    pub fn unlimited(mut self) -> Self {
+        self.config.strategy = FrameLimiterStrategy::Unlimited;
+        self
+    }
+
+    /// Target number of updates per second
+    pub fn with_update_rate(mut self, update_rate: u32) -> Self {
+        self.config.update_rate = update_rate;
+        self
+    }
+
+    /// Number of fixed updates per second
+    pub fn with_fixed_update_rate(mut self, fixed_update_rate: u32) -> Self {
+        self.config.fixed_update_rate = fixed_update_rate;
+        self
+    }
+
+    /// Maximum number of fixed updates executed in a single frame, to avoid a slow game loop to spiral down
+    pub fn with_max_fixed_updates_per_frame(mut self, max_fixed_updates_per_frame: u32) -> Self {
+        self.config.max_fixed_updates_per_frame = max_fixed_updates_per_frame;
+        self
+    }
+
+    /// Target number of renders per second
+    pub fn with_render_rate(mut self, render_rate: u32) -> Self {
+        self.config.render_rate = render_rate;
+        self
+    }
+
+    /// Present mode of the window surface, used to enable or disable the vsync
+    pub fn with_present_mode(mut self, present_mode: PresentMode) -> Self {
+        self.config.present_mode = present_mode;
+        self
+    }
+
+    /// Retrieves the configuration built
+    pub fn get(self) -> FrameLimiterConfig {
+        self.config
+    }
+}
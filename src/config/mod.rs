@@ -1,4 +1,5 @@
 //! Configurations of `Scion`
+pub mod frame_limiter_config;
 pub mod logger_config;
 pub mod scion_config;
 pub mod window_config;
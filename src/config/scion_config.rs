@@ -6,7 +6,9 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
-use crate::config::{logger_config::LoggerConfig, window_config::WindowConfig};
+use crate::config::{
+    frame_limiter_config::FrameLimiterConfig, logger_config::LoggerConfig, window_config::WindowConfig,
+};
 
 /// Main configuration used by `crate::Scion` to configure the game.
 /// Please use [`ScionConfigBuilder`] if you want to build if from code.
@@ -16,8 +18,11 @@ pub struct ScionConfig {
     pub(crate) app_name: String,
     /// Logger configuration to use.
     pub(crate) logger_config: Option<LoggerConfig>,
-    /// Window configuration to use.
+    /// Window configuration to use. If `None`, the game runs headless : no window, no rendering and no audio.
     pub(crate) window_config: Option<WindowConfig>,
+    /// Update, fixed update and render rates of the game loop.
+    #[serde(default)]
+    pub(crate) frame_limiter_config: FrameLimiterConfig,
 }
 
 impl Default for ScionConfig {
@@ -26,6 +31,7 @@ impl Default for ScionConfig {
             app_name: "Scion game".to_string(),
             logger_config: Some(Default::default()),
             window_config: Some(Default::default()),
+            frame_limiter_config: Default::default(),
         }
     }
 }
@@ -65,6 +71,19 @@ impl ScionConfigBuilder {
         self
     }
 
+    /// Sets the game loop rates configuration. `FrameLimiterConfig` can be built using `FrameLimiterConfigBuilder`
+    pub fn with_frame_limiter_config(mut self, frame_limiter_config: FrameLimiterConfig) -> Self {
+        self.config.frame_limiter_config = frame_limiter_config;
+        self
+    }
+
+    /// Removes the main window configuration, so the application runs headless (without window, rendering nor audio).
+    /// Useful for server side simulations or automated tests.
+    pub fn without_window(mut self) -> Self {
+        self.config.window_config = None;
+        self
+    }
+
     /// Retrieves the configuration built
     pub fn get(self) -> ScionConfig {
         self.config
@@ -119,4 +138,13 @@ mod tests {
         let config = ScionConfigReader::read_or_create_default_scion_json();
         assert!(config.is_ok());
     }
+
+    #[test]
+    fn test_frame_limiter_config_defaults_when_missing() {
+        let config: ScionConfig = serde_json::from_str(r#"{"app_name": "test", "logger_config": null, "window_config": null}"#)
+            .expect("A config without frame limiter must be readable");
+        assert_eq!(60, config.frame_limiter_config.update_rate);
+        assert_eq!(60, config.frame_limiter_config.fixed_update_rate);
+        assert_eq!(60, config.frame_limiter_config.render_rate);
+    }
 }
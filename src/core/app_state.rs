@@ -0,0 +1,201 @@
+//! Application states, independent from the scenes, see [`crate::ScionBuilder::with_app_state`].
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use log::info;
+
+use crate::core::world::GameData;
+
+/// `AppState` is implemented by any type usable as application state, typically a fieldless enum deriving
+/// `Debug, Copy, Clone, PartialEq, Eq, Hash`, like `enum GameFlow { Intro, Playing, Paused, GameOver }`.
+pub trait AppState: Copy + Eq + Hash + Debug + Send + Sync + 'static {}
+
+impl<T: Copy + Eq + Hash + Debug + Send + Sync + 'static> AppState for T {}
+
+/// `AppStates` is the resource holding the current application state of type `S`.
+///
+/// A transition is requested with [`AppStates::request`] and applied at the end of the frame : the `on_exit`
+/// callbacks of the current state are executed, then the `on_enter` callbacks of the new one.
+/// The `on_enter` callbacks of the initial state are executed when the application starts.
+pub struct AppStates<S: AppState> {
+    current: S,
+    previous: Option<S>,
+    requested: Option<S>,
+    entered: bool,
+    on_enter: HashMap<S, Vec<fn(&mut GameData)>>,
+    on_exit: HashMap<S, Vec<fn(&mut GameData)>>,
+}
+
+impl<S: AppState> AppStates<S> {
+    pub fn new(initial: S) -> Self {
+        Self {
+            current: initial,
+            previous: None,
+            requested: None,
+            entered: false,
+            on_enter: HashMap::new(),
+            on_exit: HashMap::new(),
+        }
+    }
+
+    /// The current state
+    pub fn current(&self) -> S {
+        self.current
+    }
+
+    /// The state before the last transition
+    pub fn previous(&self) -> Option<S> {
+        self.previous
+    }
+
+    /// Whether the current state is `state`
+    pub fn is(&self, state: S) -> bool {
+        self.current == state
+    }
+
+    /// Requests a transition to `state` at the end of the frame. The last request of the frame wins,
+    /// requesting the current state cancels the request.
+    pub fn request(&mut self, state: S) {
+        self.requested = Some(state);
+    }
+
+    /// The transition waiting for the end of the frame
+    pub fn requested(&self) -> Option<S> {
+        self.requested.filter(|requested| *requested != self.current)
+    }
+
+    /// Replaces the state entered when the application starts
+    pub(crate) fn set_initial(&mut self, initial: S) {
+        self.current = initial;
+    }
+
+    /// Adds a callback executed each time the application enters `state`
+    pub fn on_enter(&mut self, state: S, callback: fn(&mut GameData)) {
+        self.on_enter.entry(state).or_default().push(callback);
+    }
+
+    /// Adds a callback executed each time the application exits `state`
+    pub fn on_exit(&mut self, state: S, callback: fn(&mut GameData)) {
+        self.on_exit.entry(state).or_default().push(callback);
+    }
+
+    fn callbacks(callbacks: &HashMap<S, Vec<fn(&mut GameData)>>, state: S) -> Vec<fn(&mut GameData)> {
+        callbacks.get(&state).cloned().unwrap_or_default()
+    }
+}
+
+/// Transition functions of the registered state types, so that the runner applies them without knowing the types
+#[derive(Default)]
+pub(crate) struct AppStateTransitions(Vec<fn(&mut GameData)>);
+
+impl AppStateTransitions {
+    pub(crate) fn register<S: AppState>(&mut self) {
+        self.0.push(apply_transition::<S>);
+    }
+}
+
+/// Enters the initial states if needed, then applies the requested transitions
+pub(crate) fn apply_app_state_transitions(data: &mut GameData) {
+    let transitions = match data.get_resource::<AppStateTransitions>() {
+        Some(transitions) => transitions.0.clone(),
+        None => return,
+    };
+    transitions.iter().for_each(|transition| transition(data));
+}
+
+fn apply_transition<S: AppState>(data: &mut GameData) {
+    let (exit, enter) = {
+        let mut states = match data.get_resource_mut::<AppStates<S>>() {
+            Some(states) => states,
+            None => return,
+        };
+        if !states.entered {
+            states.entered = true;
+            (vec![], AppStates::callbacks(&states.on_enter, states.current))
+        } else {
+            match states.requested.take() {
+                Some(requested) if requested != states.current => {
+                    info!("Application state changed from {:?} to {:?}", states.current, requested);
+                    let exit = AppStates::callbacks(&states.on_exit, states.current);
+                    states.previous = Some(states.current);
+                    states.current = requested;
+                    (exit, AppStates::callbacks(&states.on_enter, requested))
+                }
+                _ => return,
+            }
+        }
+    };
+    exit.iter().chain(enter.iter()).for_each(|callback| callback(data));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::scion_config::ScionConfigBuilder;
+    use crate::core::scheduler::SystemConfigBuilder;
+    use crate::ScionBuilder;
+
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    enum Flow {
+        Intro,
+        Playing,
+        Paused,
+    }
+
+    fn log(data: &mut GameData, value: &str) {
+        let mut state = data.game_state_mut();
+        let log = state.get_text("log").unwrap_or_default();
+        state.set_text("log", &format!("{}{}", log, value));
+    }
+
+    fn playing_system(data: &mut GameData) {
+        log(data, "u");
+        if data.game_state().get_bool("pause") {
+            data.app_state::<Flow>().request(Flow::Paused);
+        }
+    }
+
+    #[test]
+    fn app_state_transitions_test() {
+        let mut app = ScionBuilder::new(ScionConfigBuilder::new().without_window().get())
+            .with_app_state(Flow::Intro)
+            .with_on_enter(Flow::Intro, |data| log(data, "I"))
+            .with_on_exit(Flow::Intro, |data| log(data, "i"))
+            .with_on_enter(Flow::Playing, |data| log(data, "P"))
+            .with_on_exit(Flow::Playing, |data| log(data, "p"))
+            .with_on_enter(Flow::Paused, |data| log(data, "Z"))
+            .with_system_config(SystemConfigBuilder::new(playing_system).in_states(&[Flow::Playing]).get())
+            .build_test_app();
+
+        app.step();
+        assert_eq!(Some("I".to_string()), app.game_data().game_state().get_text("log"));
+
+        app.game_data().app_state::<Flow>().request(Flow::Playing);
+        app.step();
+        app.step();
+        app.game_data().game_state_mut().set_bool("pause", true);
+        app.step();
+        app.step();
+
+        let states = app.game_data().app_state::<Flow>();
+        assert!(states.is(Flow::Paused));
+        assert_eq!(Some(Flow::Playing), states.previous());
+        assert_eq!(Some("IiPuupZ".to_string()), app.game_data().game_state().get_text("log"));
+    }
+
+    #[test]
+    fn app_state_registered_twice_test() {
+        let mut app = ScionBuilder::new(ScionConfigBuilder::new().without_window().get())
+            .with_app_state(Flow::Intro)
+            .with_on_enter(Flow::Playing, |data| log(data, "P"))
+            .with_app_state(Flow::Playing)
+            .build_test_app();
+
+        app.step();
+        assert!(app.game_data().app_state::<Flow>().is(Flow::Playing));
+        assert_eq!(Some("P".to_string()), app.game_data().game_state().get_text("log"));
+    }
+}
@@ -1,9 +1,17 @@
+use std::path::Path;
+
 use crate::config::scion_config::ScionConfig;
+use crate::core::app_state::{AppState, AppStateTransitions, AppStates};
 use crate::core::package::Package;
+use crate::core::resources::audio::Audio;
+use crate::core::resources::save_manager::SaveManager;
 use crate::core::scene::{Scene, SceneMachine};
-use crate::core::scheduler::Scheduler;
+use crate::core::scheduler::{ExecutionMode, Scheduler, SystemConfig, SystemConfigBuilder};
+use crate::core::scion_runner::ScionRunner;
 use crate::core::state::GameState;
+use crate::core::system_param::SystemParamFunction;
 use crate::core::systems::InternalPackage;
+use crate::core::test_app::ScionTestApp;
 use crate::core::world::GameData;
 use crate::Scion;
 
@@ -15,31 +23,119 @@ pub struct ScionBuilder {
     scheduler: Scheduler,
     scene: Option<Box<dyn Scene + Send>>,
     world: GameData,
+    execution_mode: Option<ExecutionMode>,
 }
 
 impl ScionBuilder {
     pub fn new(config: ScionConfig) -> Self {
-        let builder = Self {
+        let mut builder = Self {
             config,
             scheduler: Default::default(),
             scene: Default::default(),
             world: Default::default(),
+            execution_mode: None,
         };
+        builder.world.insert_resource(SaveManager::new(&builder.config.app_name));
         builder.with_package(InternalPackage)
     }
 
-    /// Specify a system to add to the scheduler.
-    pub fn with_system(mut self, system: fn(&mut GameData)) -> Self {
+    /// Specify a system to add to the scheduler. It can be a function or a closure keeping its own state.
+    pub fn with_system<F: FnMut(&mut GameData) + Send + 'static>(mut self, system: F) -> Self {
         self.scheduler.add_system(system);
         self
     }
 
     /// Specify a system to add to the scheduler with a conditional pausing flag function.
-    pub fn with_pausable_system(mut self, system: fn(&mut GameData), pause_condition: fn(&GameState) -> bool) -> Self {
+    pub fn with_pausable_system<F: FnMut(&mut GameData) + Send + 'static>(mut self, system: F, pause_condition: fn(&GameState) -> bool) -> Self {
         self.scheduler.add_pausable_system(system, pause_condition);
         self
     }
 
+    /// Registers the application state type `S`, starting in `initial`. The current state is available with
+    /// [`GameData::app_state`], which is also used to request transitions. States are independent from the scenes.
+    /// Registering `S` again only changes its initial state, the callbacks already added are kept.
+    pub fn with_app_state<S: AppState>(mut self, initial: S) -> Self {
+        if self.world.get_resource::<AppStates<S>>().is_some() {
+            self.world.app_state::<S>().set_initial(initial);
+        } else {
+            if self.world.get_resource::<AppStateTransitions>().is_none() {
+                self.world.insert_resource(AppStateTransitions::default());
+            }
+            self.world.get_resource_mut::<AppStateTransitions>().expect("Transitions have just been inserted").register::<S>();
+            self.world.insert_resource(AppStates::new(initial));
+        }
+        self
+    }
+
+    /// Adds a callback executed each time the application enters `state`. The state type must be registered first with
+    /// [`ScionBuilder::with_app_state`].
+    pub fn with_on_enter<S: AppState>(self, state: S, callback: fn(&mut GameData)) -> Self {
+        self.world.app_state::<S>().on_enter(state, callback);
+        self
+    }
+
+    /// Adds a callback executed each time the application exits `state`. The state type must be registered first with
+    /// [`ScionBuilder::with_app_state`].
+    pub fn with_on_exit<S: AppState>(self, state: S, callback: fn(&mut GameData)) -> Self {
+        self.world.app_state::<S>().on_exit(state, callback);
+        self
+    }
+
+    /// Specify a system to add to the scheduler, only executed when the current application state of type `S` is
+    /// one of `states`.
+    pub fn with_state_system<S: AppState, F: FnMut(&mut GameData) + Send + 'static>(self, system: F, states: &[S]) -> Self {
+        self.with_system_config(SystemConfigBuilder::new(system).in_states(states).get())
+    }
+
+    /// Specify a typed system to add to the scheduler, a function whose parameters are resources or queries
+    /// (see [`crate::core::system_param`]). Panics if its parameters borrow the same data in conflicting ways.
+    pub fn with_typed_system<F: SystemParamFunction<P>, P: 'static>(self, system: F) -> Self {
+        match SystemConfigBuilder::typed(system) {
+            Ok(builder) => self.with_system_config(builder.get()),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Specify a system to add to the scheduler, with its stage, label and ordering constraints.
+    /// See [`crate::core::scheduler::SystemConfigBuilder`], which also accepts structs implementing [`crate::core::scheduler::System`].
+    pub fn with_system_config(mut self, config: SystemConfig) -> Self {
+        self.scheduler.add_system_config(config);
+        self
+    }
+
+    /// Specify how the systems of the application are executed. Defaults to [`ExecutionMode::Parallel`] when running
+    /// the application and to the deterministic [`ExecutionMode::Sequential`] in a test app.
+    /// Systems registered by scenes are always executed sequentially.
+    pub fn with_execution_mode(mut self, mode: ExecutionMode) -> Self {
+        self.execution_mode = Some(mode);
+        self
+    }
+
+    /// Enables the [`crate::core::resources::profiler::Profiler`] from the start of the application,
+    /// to record the timings of the systems, scene hooks and rendering phases.
+    pub fn with_profiler(self) -> Self {
+        self.world.profiler().enable();
+        self
+    }
+
+    /// Records the messages published in the event topics from the start of the application to the json-lines file
+    /// at `path`, see [`crate::core::resources::events::Events::start_recording`]. Panics if the file can't be created.
+    pub fn with_event_recording(self, path: &Path) -> Self {
+        if let Err(e) = self.world.events().start_recording(path) {
+            panic!("Unable to record the events to {:?}: {:?}", path, e);
+        }
+        self
+    }
+
+    /// Replays the messages recorded in the file at `path` at the same frames, from the start of the application,
+    /// see [`crate::core::resources::events::Events::start_replay`]. Panics if the file can't be read.
+    pub fn with_event_replay(self, path: &Path) -> Self {
+        if let Err(e) = self.world.events().start_replay(path) {
+            panic!("Unable to replay the events of {:?}: {:?}", path, e);
+        }
+        self
+    }
+
     /// Set the scene to the given one. Only one scene can be executed at a time
     pub fn with_scene<T: Scene + Default + Send + 'static>(mut self) -> Self {
         self.scene = Some(Box::<T>::default());
@@ -53,13 +149,39 @@ impl ScionBuilder {
     }
 
     /// Builds, setups and runs the Scion application, must be called at the end of the building process.
-    pub fn run(self) {
+    pub fn run(mut self) {
+        self.build_scheduler(ExecutionMode::Parallel);
         let scion = Scion {
             config: self.config,
             game_data: self.world,
             scheduler: self.scheduler,
-            layer_machine: SceneMachine { current_scene: self.scene, current_scene_started: false },
+            layer_machine: SceneMachine::new(self.scene),
         };
         scion.run();
     }
-}
\ No newline at end of file
+
+    /// Builds a headless [`ScionTestApp`] that can be stepped frame by frame with a fixed frame duration.
+    /// The window configuration is ignored, no rendering nor audio will happen.
+    pub fn build_test_app(mut self) -> ScionTestApp {
+        self.build_scheduler(ExecutionMode::Sequential);
+        self.world.insert_resource(Audio::disabled());
+        ScionTestApp::new(ScionRunner {
+            game_data: self.world,
+            scheduler: self.scheduler,
+            layer_machine: SceneMachine::new(self.scene),
+            window_rendering_manager: None,
+            window: None,
+            main_thread_receiver: None,
+            main_thread_proxy: None,
+            scion_pre_renderer: Default::default(),
+            frame_limiter_config: self.config.frame_limiter_config,
+        })
+    }
+
+    fn build_scheduler(&mut self, default_mode: ExecutionMode) {
+        self.scheduler.set_execution_mode(self.execution_mode.unwrap_or(default_mode));
+        if let Err(e) = self.scheduler.build() {
+            panic!("Unable to order the systems: {}", e);
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 use std::io::BufReader;
-use std::sync::mpsc::Receiver;
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::time::Duration;
 
-use log::debug;
+use log::{debug, warn};
 use rodio::{OutputStream, Sink, Source};
 
 use crate::core::resources::audio::AudioEvent;
@@ -19,12 +19,18 @@ impl AudioController {
 }
 
 pub(crate) fn audio_thread(controller: AudioController) {
-    let (_stream, stream_handle) = OutputStream::try_default().unwrap();
+    let (_stream, stream_handle) = match OutputStream::try_default() {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("No audio output device available, sounds will not be played: {:?}", e);
+            return;
+        }
+    };
     let mut sinks: HashMap<usize, Sink> = HashMap::new();
 
     loop {
-        if let Ok(message) = controller.receiver.recv_timeout(Duration::from_secs(1)) {
-            match message {
+        match controller.receiver.recv_timeout(Duration::from_secs(1)) {
+            Ok(message) => match message {
                 AudioEvent::PlaySound { path, config, sound_id } => {
                     debug!("Started to play sound {}", path);
                     let sink = Sink::try_new(&stream_handle).unwrap();
@@ -45,10 +51,14 @@ pub(crate) fn audio_thread(controller: AudioController) {
                         drop(sink);
                     }
                 }
-            }
+            },
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
         }
         sinks.retain(|&_k, sink| {
             !sink.empty()
         });
     }
+    sinks.values().for_each(|sink| sink.stop());
+    debug!("Audio thread stopped");
 }
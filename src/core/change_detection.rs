@@ -0,0 +1,416 @@
+//! Engine level detection of the components added, changed or removed since a system last ran.
+//!
+//! Each modification of the world is stamped with a change tick. Additions and removals are always detected, whether
+//! they are made through [`crate::core::world::World`] or [`crate::core::resources::commands::Commands`].
+//! Mutations are detected when made through a [`Tracked`] query, or signaled with [`ChangeTracker::mark_changed`] ;
+//! components mutated with `query_mut` or `entry_mut` are not detected, except the
+//! [`crate::core::components::maths::transform::Transform`]s : their setters flag them as dirty, and the internal
+//! `dirty_transform_system` reports them during [`crate::core::scheduler::Stage::PreRender`]. A transform mutated
+//! before it is seen as changed by the systems executed after it, a transform mutated later is seen from the next
+//! frame's `PreRender`. The renderer only rebuilds the transform uniforms of the entities whose transform changed.
+//! The other components flagged as dirty by their setters, like the sprites, texts, inputs or polygons, are not
+//! reported : mutate them through a [`Tracked`] query to detect it.
+//!
+//! Modifications made through a mutable world are recorded without locking. The ticks are kept in the order they
+//! happened, so finding what happened since a tick only goes through the modifications made after it.
+//!
+//! Typed systems use the [`Added`], [`Changed`], [`Removed`] and [`Tracked`] parameters, which only return what
+//! happened since the previous execution of the system. Other systems can query the [`ChangeTracker`] of the
+//! `GameData` with a tick they saved.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+
+use hecs::{Component, Entity, QueryBorrow};
+
+use crate::core::system_param::{SystemAccess, SystemParam};
+use crate::core::world::{GameData, World};
+
+/// Ticks given to a typed system when it is executed
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SystemTicks {
+    /// Tick of the previous execution of the system, 0 if it never ran
+    pub(crate) last_run: u64,
+    /// Tick of the current execution
+    pub(crate) this_run: u64,
+}
+
+/// Latest tick of each entity, and the log of these ticks sorted by tick
+#[derive(Default)]
+struct EntityTicks {
+    latest: HashMap<Entity, u64>,
+    log: Vec<(u64, Entity)>,
+}
+
+impl EntityTicks {
+    fn insert(&mut self, entity: Entity, tick: u64) {
+        if self.latest.insert(entity, tick) == Some(tick) {
+            return;
+        }
+        // Ticks only go backward when a parallel system records a mutation after a more recent system
+        match self.log.last() {
+            Some((last, _)) if *last > tick => {
+                let index = self.log.partition_point(|(t, _)| *t <= tick);
+                self.log.insert(index, (tick, entity));
+            }
+            _ => self.log.push((tick, entity)),
+        }
+        self.compact_if_stale();
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        if self.latest.remove(&entity).is_some() {
+            self.compact_if_stale();
+        }
+    }
+
+    fn contains(&self, entity: Entity) -> bool {
+        self.latest.contains_key(&entity)
+    }
+
+    fn since(&self, since: u64) -> Vec<Entity> {
+        let start = self.log.partition_point(|(t, _)| *t <= since);
+        self.log[start..].iter().filter(|(t, e)| self.latest.get(e) == Some(t)).map(|(_, e)| *e).collect()
+    }
+
+    /// Forgets the outdated ticks of the log once they are as many as the entities, to keep the cost amortized
+    fn compact_if_stale(&mut self) {
+        if self.log.len() > 2 * self.latest.len() + 32 {
+            let latest = &self.latest;
+            self.log.retain(|(t, e)| latest.get(e) == Some(t));
+        }
+    }
+}
+
+#[derive(Default)]
+struct ComponentTicks {
+    added: EntityTicks,
+    changed: EntityTicks,
+    /// Sorted by tick, as removals are only recorded through a mutable world
+    removed: Vec<(Entity, u64)>,
+}
+
+/// `ChangeTracker` records, for each component type, when it was added to, changed on or removed from each entity.
+pub struct ChangeTracker {
+    tick: AtomicU64,
+    /// Tick at the end of the previous frame, removals older than it are forgotten at the end of the frame
+    last_frame_end: u64,
+    /// Ticks of each component type, locked separately so that systems mutating different components don't wait
+    /// for each other
+    components: RwLock<HashMap<TypeId, Mutex<ComponentTicks>>>,
+}
+
+impl Default for ChangeTracker {
+    fn default() -> Self {
+        // Starts at 1 so that a system that never ran, with a last run at 0, sees everything
+        Self { tick: AtomicU64::new(1), last_frame_end: 0, components: Default::default() }
+    }
+}
+
+impl ChangeTracker {
+    /// Current change tick, to save and give back to [`ChangeTracker::added`], [`ChangeTracker::changed`] or
+    /// [`ChangeTracker::removed`] to know what happened since
+    pub fn change_tick(&self) -> u64 {
+        self.tick.load(Ordering::Acquire)
+    }
+
+    /// Entities to which `T` was added after `since`
+    pub fn added<T: Component>(&self, since: u64) -> Vec<Entity> {
+        self.read::<T, _>(|ticks| ticks.added.since(since))
+    }
+
+    /// Entities whose `T` was added or changed after `since`
+    pub fn changed<T: Component>(&self, since: u64) -> Vec<Entity> {
+        self.read::<T, _>(|ticks| ticks.changed.since(since))
+    }
+
+    /// Entities from which `T` was removed, or that were removed with a `T`, after `since`.
+    /// Removals are kept until the end of the frame following the one they happened in.
+    pub fn removed<T: Component>(&self, since: u64) -> Vec<Entity> {
+        self.read::<T, _>(|ticks| {
+            let start = ticks.removed.partition_point(|(_, t)| *t <= since);
+            ticks.removed[start..].iter().map(|(e, _)| *e).collect()
+        })
+    }
+
+    /// Signals that the component `T` of `entity` was mutated
+    pub fn mark_changed<T: Component>(&self, entity: Entity) {
+        self.record_changed(TypeId::of::<T>(), entity, self.change_tick());
+    }
+
+    pub(crate) fn record_changed(&self, type_id: TypeId, entity: Entity, tick: u64) {
+        let components = self.components.read().expect("Change tracker lock is poisoned");
+        if let Some(ticks) = components.get(&type_id) {
+            ticks.lock().expect("Change tracker lock is poisoned").changed.insert(entity, tick);
+            return;
+        }
+        drop(components);
+        self.components
+            .write()
+            .expect("Change tracker lock is poisoned")
+            .entry(type_id)
+            .or_default()
+            .get_mut()
+            .expect("Change tracker lock is poisoned")
+            .changed
+            .insert(entity, tick);
+    }
+
+    /// Records that `entity` received a component of type `type_id`, as an addition if it didn't have one,
+    /// otherwise as a change
+    pub(crate) fn record_inserted(&mut self, type_id: TypeId, entity: Entity) {
+        let tick = self.change_tick();
+        let ticks = self.ticks_mut(type_id);
+        if !ticks.added.contains(entity) {
+            ticks.added.insert(entity, tick);
+        }
+        ticks.changed.insert(entity, tick);
+    }
+
+    pub(crate) fn record_removed(&mut self, type_id: TypeId, entity: Entity) {
+        let tick = self.change_tick();
+        let ticks = self.ticks_mut(type_id);
+        ticks.added.remove(entity);
+        ticks.changed.remove(entity);
+        ticks.removed.push((entity, tick));
+    }
+
+    /// Starts the execution of a system and returns its tick
+    pub(crate) fn begin_system(&self) -> u64 {
+        self.tick.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    /// Ends the execution of a system, so that what happens next is seen as newer than it by this system
+    pub(crate) fn end_system(&self) {
+        self.tick.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Forgets the removals that happened before the previous frame
+    pub(crate) fn end_frame(&mut self) {
+        let last_frame_end = self.last_frame_end;
+        self.components.get_mut().expect("Change tracker lock is poisoned").values_mut().for_each(|ticks| {
+            ticks.get_mut().expect("Change tracker lock is poisoned").removed.retain(|(_, tick)| *tick > last_frame_end)
+        });
+        self.last_frame_end = self.change_tick();
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.components.get_mut().expect("Change tracker lock is poisoned").clear();
+    }
+
+    fn read<T: Component, R: Default>(&self, read: impl FnOnce(&ComponentTicks) -> R) -> R {
+        self.components
+            .read()
+            .expect("Change tracker lock is poisoned")
+            .get(&TypeId::of::<T>())
+            .map_or_else(R::default, |ticks| read(&ticks.lock().expect("Change tracker lock is poisoned")))
+    }
+
+    fn ticks_mut(&mut self, type_id: TypeId) -> &mut ComponentTicks {
+        self.components
+            .get_mut()
+            .expect("Change tracker lock is poisoned")
+            .entry(type_id)
+            .or_default()
+            .get_mut()
+            .expect("Change tracker lock is poisoned")
+    }
+}
+
+macro_rules! entities_param {
+    ($(#[$doc:meta])* $name:ident, $method:ident, $borrows:expr) => {
+        $(#[$doc])*
+        pub struct $name<T: Component> {
+            entities: Vec<Entity>,
+            _component: PhantomData<fn() -> T>,
+        }
+
+        impl<T: Component> $name<T> {
+            pub fn iter(&self) -> impl Iterator<Item = &Entity> {
+                self.entities.iter()
+            }
+
+            pub fn contains(&self, entity: &Entity) -> bool {
+                self.entities.contains(entity)
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.entities.is_empty()
+            }
+        }
+
+        impl<T: Component> SystemParam for $name<T> {
+            type Item<'a> = $name<T>;
+
+            fn access(access: &mut SystemAccess) {
+                if $borrows {
+                    access.add_component::<T>(false);
+                }
+            }
+
+            fn fetch(data: &GameData, ticks: SystemTicks) -> $name<T> {
+                $name { entities: data.changes().$method::<T>(ticks.last_run), _component: PhantomData }
+            }
+        }
+    };
+}
+
+entities_param!(
+    /// Entities to which `T` was added since the previous execution of the system
+    Added, added, true
+);
+entities_param!(
+    /// Entities whose `T` was added or changed since the previous execution of the system
+    Changed, changed, true
+);
+entities_param!(
+    /// Entities from which `T` was removed since the previous execution of the system
+    Removed, removed, false
+);
+
+/// Query over the entities having a `T`, that detects which ones are mutated
+pub struct Tracked<'a, T: Component> {
+    query: QueryBorrow<'a, &'static mut T>,
+    changes: &'a ChangeTracker,
+    tick: u64,
+}
+
+impl<'a, T: Component> Tracked<'a, T> {
+    /// Iterates over the entities having a `T`. A component is marked as changed when it is mutably dereferenced.
+    pub fn iter<'q>(&'q mut self) -> impl Iterator<Item = (Entity, Mut<'q, T>)> + 'q {
+        let (changes, tick): (&'q ChangeTracker, u64) = (self.changes, self.tick);
+        self.query.iter().map(move |(entity, value)| (entity, Mut { value, entity, changes, tick, changed: false }))
+    }
+}
+
+impl<T: Component> SystemParam for Tracked<'_, T> {
+    type Item<'a> = Tracked<'a, T>;
+
+    fn access(access: &mut SystemAccess) {
+        access.add_component::<T>(true);
+    }
+
+    fn fetch(data: &GameData, ticks: SystemTicks) -> Tracked<'_, T> {
+        Tracked { query: data.query::<&'static mut T>(), changes: data.changes(), tick: ticks.this_run }
+    }
+}
+
+/// Mutable access to a component, that marks it as changed when it is mutably dereferenced
+pub struct Mut<'a, T: Component> {
+    value: &'a mut T,
+    entity: Entity,
+    changes: &'a ChangeTracker,
+    tick: u64,
+    changed: bool,
+}
+
+impl<T: Component> Deref for Mut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: Component> DerefMut for Mut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.changed = true;
+        self.value
+    }
+}
+
+impl<T: Component> Drop for Mut<'_, T> {
+    fn drop(&mut self) {
+        if self.changed {
+            self.changes.record_changed(TypeId::of::<T>(), self.entity, self.tick);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::scheduler::System;
+    use crate::core::system_param::{ResMut, TypedSystem};
+
+    use super::*;
+
+    struct Health(u32);
+
+    #[derive(Default)]
+    struct Report(Vec<String>);
+
+    fn report_system(mut report: ResMut<Report>, added: Added<Health>, changed: Changed<Health>, removed: Removed<Health>) {
+        report.0.push(format!("{}{}{}", added.iter().count(), changed.iter().count(), removed.iter().count()));
+    }
+
+    fn damage_system(mut query: Tracked<Health>) {
+        for (_, mut health) in query.iter() {
+            if health.0 > 5 {
+                health.0 -= 1;
+            }
+        }
+    }
+
+    #[test]
+    fn typed_systems_see_changes_since_last_run_test() {
+        let mut data = GameData::default();
+        data.insert_resource(Report::default());
+        let mut report = TypedSystem::new(report_system).unwrap();
+        let mut damage = TypedSystem::new(damage_system).unwrap();
+
+        let strong = data.push((Health(10),));
+        let weak = data.push((Health(2),));
+        report.run(&mut data);
+        report.run(&mut data);
+
+        damage.run(&mut data);
+        report.run(&mut data);
+
+        let _r = data.remove_component::<Health>(strong);
+        data.changes().mark_changed::<Health>(weak);
+        report.run(&mut data);
+
+        assert_eq!(vec!["220", "000", "010", "011"], data.get_resource::<Report>().unwrap().0);
+    }
+
+    #[test]
+    fn removals_are_forgotten_after_a_frame_test() {
+        let mut data = GameData::default();
+        let entity = data.push((Health(1), 2u8));
+        let _r = data.add_components(entity, (Health(3),));
+        assert_eq!(vec![entity], data.changes().changed::<Health>(0));
+
+        let _r = data.remove(entity);
+        assert_eq!(vec![entity], data.changes().removed::<u8>(0));
+        assert!(data.changes().changed::<Health>(0).is_empty());
+
+        data.subworld.changes.end_frame();
+        assert_eq!(1, data.changes().removed::<u8>(0).len());
+        data.subworld.changes.end_frame();
+        assert!(data.changes().removed::<u8>(0).is_empty());
+    }
+
+    #[test]
+    fn changes_since_a_tick_test() {
+        let mut data = GameData::default();
+        let first = data.push((Health(1),));
+        let second = data.push((Health(2),));
+        let since = data.changes().begin_system();
+        for _ in 0..100 {
+            data.changes().mark_changed::<Health>(second);
+            data.changes().end_system();
+        }
+        data.changes().record_changed(TypeId::of::<Health>(), first, since);
+        data.changes().end_system();
+
+        assert_eq!(vec![second], data.changes().changed::<Health>(since));
+        assert_eq!(2, data.changes().changed::<Health>(since - 1).len());
+        assert!(data.changes().added::<Health>(since).is_empty());
+        assert_eq!(2, data.changes().added::<Health>(0).len());
+        assert!(data.changes().changed::<u8>(0).is_empty());
+    }
+}
@@ -1,7 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
 pub(crate) struct DefaultCamera;
 
 /// Mandatory component to add to the World to have anything rendered.
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct Camera {
     pub(crate) left: f32,
     pub(crate) right: f32,
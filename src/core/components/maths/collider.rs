@@ -1,12 +1,14 @@
 use geo_clipper::Clipper;
 use geo_types::{Coord, LineString};
 use hecs::Entity;
+use serde::{Deserialize, Serialize};
 
 use crate::core::components::maths::{coordinates::Coordinates, Pivot, transform::Transform};
+use crate::core::snapshot::{EntityMap, MapEntities};
 use crate::utils::maths::{centroid_polygon, rotate_point_around_pivot, Vector};
 
 /// `ColliderMask` will serve as a 'mask' to allow filter while collisions happen
-#[derive(PartialEq, Clone, Eq, Hash, Debug)]
+#[derive(PartialEq, Clone, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum ColliderMask {
     None,
     Character,
@@ -18,7 +20,7 @@ pub enum ColliderMask {
 }
 
 /// `ColliderType` will determine the shape of the collider.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum ColliderType {
     SquareCollider(usize),
     RectangleCollider(usize, usize),
@@ -26,7 +28,7 @@ pub enum ColliderType {
 }
 
 /// The main collider representation to add to an entity, using the new function
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Collider {
     collider_mask: ColliderMask,
     collider_type: ColliderType,
@@ -208,10 +210,19 @@ impl Collider {
     }
 }
 
+/// Collisions with entities missing from the snapshot are dropped
+impl MapEntities for Collider {
+    fn map_entities(&mut self, map: &EntityMap) -> bool {
+        self.collisions.retain_mut(|collision| map.get(collision.entity).map(|entity| collision.entity = entity).is_some());
+        true
+    }
+}
+
 /// Representation of a collision
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Collision {
     pub(crate) mask: ColliderMask,
+    #[serde(with = "crate::core::snapshot::serde_entity")]
     pub(crate) entity: Entity,
     pub(crate) coordinates: Coordinates,
     pub(crate) collision_area: CollisionArea,
@@ -232,7 +243,7 @@ impl Collision {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CollisionArea {
     pub(crate) coordinates: Vec<Coordinates>,
 }
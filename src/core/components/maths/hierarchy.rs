@@ -1,11 +1,29 @@
 use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+use crate::core::snapshot::{EntityMap, MapEntities};
 
 /// A component creating a parent link to the wrapped entity
-#[derive(Debug)]
-pub struct Parent(pub Entity);
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Parent(#[serde(with = "crate::core::snapshot::serde_entity")] pub Entity);
 
 /// A component creating a link to the wrapped entities
 /// This component will be automatically added to an entity by Scion
 /// if a component references this entity with a [`Parent`] component
-#[derive(Debug)]
-pub struct Children(pub Vec<Entity>);
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Children(#[serde(with = "crate::core::snapshot::serde_entities")] pub Vec<Entity>);
+
+/// A child of an entity missing from the snapshot is restored without its `Parent`
+impl MapEntities for Parent {
+    fn map_entities(&mut self, map: &EntityMap) -> bool {
+        map.get(self.0).map(|parent| self.0 = parent).is_some()
+    }
+}
+
+/// Children missing from the snapshot are dropped, and so is the component if none remains
+impl MapEntities for Children {
+    fn map_entities(&mut self, map: &EntityMap) -> bool {
+        self.0 = self.0.iter().filter_map(|child| map.get(*child)).collect();
+        !self.0.is_empty()
+    }
+}
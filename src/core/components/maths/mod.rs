@@ -5,8 +5,10 @@ pub mod hierarchy;
 pub mod transform;
 pub mod padding;
 
+use serde::{Deserialize, Serialize};
+
 /// `Pivot` tells where the pivot point of a component is
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum Pivot {
     /// Pivot is on the top left corner of the shape
     TopLeft,
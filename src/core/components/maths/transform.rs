@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{core::components::maths::coordinates::Coordinates, utils::maths::Vector};
 
 /// represents the bounds for a Transoform with min and max values
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Default, Debug, Copy, Clone, Serialize, Deserialize)]
 struct Bounds {
     pub(crate) min_x: Option<f32>,
     pub(crate) max_x: Option<f32>,
@@ -11,7 +13,7 @@ struct Bounds {
 
 /// Component used by the renderer to know where and how to represent an object.
 /// Default is position 0;0 with a scale of 1.0 and no angle.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct Transform {
     pub(crate) local_translation: Coordinates,
     pub(crate) global_translation: Coordinates,
@@ -134,7 +136,8 @@ impl Transform {
 
     /// Change the scale value to a new one.
     pub fn set_scale(&mut self, scale: f32) {
-        self.scale = scale
+        self.scale = scale;
+        self.dirty = true;
     }
 
     /// Change the z value in the local translation coordinates.
@@ -164,29 +167,34 @@ impl Transform {
     /// Configure the minimum global x position for this transform to be min_x
     pub fn set_min_x(&mut self, min_x: Option<f32>) {
         self.bounds.min_x = min_x;
+        self.dirty = true;
         self.handle_bounds();
     }
 
     /// Configure the maximum global x position for this transform to be max_x
     pub fn set_max_x(&mut self, max_x: Option<f32>) {
         self.bounds.max_x = max_x;
+        self.dirty = true;
         self.handle_bounds();
     }
 
     /// Configure the minimum global y position for this transform to be min_x
     pub fn set_min_y(&mut self, min_y: Option<f32>) {
         self.bounds.min_y = min_y;
+        self.dirty = true;
         self.handle_bounds();
     }
 
     /// Configure the maximum global y position for this transform to be max_x
     pub fn set_max_y(&mut self, max_y: Option<f32>) {
         self.bounds.max_y = max_y;
+        self.dirty = true;
         self.handle_bounds();
     }
 
     pub fn set_use_screen_as_origin(&mut self, new_value: bool) {
         self.use_screen_as_origin = new_value;
+        self.dirty = true;
         self.handle_bounds();
     }
 
@@ -202,6 +210,7 @@ impl Transform {
         self.bounds.max_x = max_x;
         self.bounds.min_y = min_y;
         self.bounds.max_y = max_y;
+        self.dirty = true;
         self.handle_bounds();
     }
 
@@ -220,6 +229,15 @@ impl Transform {
         self.global_angle = self.local_angle + parent_angle;
     }
 
+    /// Returns a copy of this transform, with global translation and angle interpolated from the previous ones.
+    pub(crate) fn interpolated_from(&self, previous_translation: &Coordinates, previous_angle: f32, alpha: f32) -> Transform {
+        let mut transform = *self;
+        transform.global_translation.x = previous_translation.x + (self.global_translation.x - previous_translation.x) * alpha;
+        transform.global_translation.y = previous_translation.y + (self.global_translation.y - previous_translation.y) * alpha;
+        transform.global_angle = previous_angle + (self.global_angle - previous_angle) * alpha;
+        transform
+    }
+
     fn handle_bounds(&mut self) {
         if let Some(min_x) = self.bounds.min_x {
             if self.global_translation.x < min_x {
@@ -247,6 +265,15 @@ impl Transform {
     }
 }
 
+/// Component telling the renderer to draw the [`Transform`] of its entity interpolated between the entity's
+/// two last fixed updates, using [`crate::core::resources::time::Time::interpolation_alpha`].
+/// Useful for entities moved in `on_fixed_update`, to have a smooth movement whatever the render rate.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Interpolated {
+    /// Global translation and angle of the transform before the last fixed update
+    pub(crate) previous: Option<(Coordinates, f32)>,
+}
+
 pub struct TransformBuilder {
     transform: Transform,
 }
@@ -349,6 +376,16 @@ mod tests {
         assert!(transform.dirty);
     }
 
+    #[test]
+    fn interpolated_from_test() {
+        let transform = Transform::new(Coordinates::new(10., 20.), 1., 2.);
+        let interpolated = transform.interpolated_from(&Coordinates::new(0., 10.), 0., 0.25);
+        assert_eq!(2.5, interpolated.global_translation.x);
+        assert_eq!(12.5, interpolated.global_translation.y);
+        assert_eq!(0.5, interpolated.global_angle);
+        assert_eq!(10., interpolated.local_translation.x);
+    }
+
     #[test]
     fn handle_bounds_test() {
         let mut t = Transform::default();
@@ -1 +1,2 @@
-pub mod maths;
\ No newline at end of file
+pub mod maths;
+pub mod name;
\ No newline at end of file
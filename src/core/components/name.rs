@@ -0,0 +1,136 @@
+//! Components used to find entities without keeping their ids around.
+
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+use hecs::Entity;
+use serde::{Deserialize, Serialize};
+
+/// Name of an entity, indexed by the `GameData` to find it with [`crate::core::world::GameData::find_by_name`] or
+/// [`crate::core::world::GameData::find_by_path`]. Names don't have to be unique.
+///
+/// Add a new `Name` to the entity to rename it. A name replaced in place, through a mutable query, is only
+/// indexed again during the next [`crate::core::scheduler::Stage::PreUpdate`], until then the entity isn't found
+/// by its new name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Name(String);
+
+impl Name {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Name {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Tags of an entity, indexed by the `GameData` to find all the entities having a tag with
+/// [`crate::core::world::GameData::find_all_with_tag`].
+///
+/// Add new `Tags` to the entity to change them. Tags replaced in place, through a mutable query, are only indexed
+/// again during the next [`crate::core::scheduler::Stage::PreUpdate`], until then the entity isn't found by its
+/// new tags.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tags(HashSet<String>);
+
+impl Tags {
+    pub fn new<S: Into<String>>(tags: impl IntoIterator<Item = S>) -> Self {
+        Self(tags.into_iter().map(|tag| tag.into()).collect())
+    }
+
+    /// Returns new tags made of these ones and `tag`
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.0.insert(tag.into());
+        self
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.contains(tag)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.0.iter()
+    }
+}
+
+/// Index of the entities by name and by tag, maintained by the `SubWorld`
+#[derive(Default)]
+pub(crate) struct NameIndex {
+    names: HashMap<String, Vec<Entity>>,
+    tags: HashMap<String, Vec<Entity>>,
+    /// Indexed name and tags of each entity, as they may have been replaced in place since
+    indexed: HashMap<Entity, (Option<Name>, Option<Tags>)>,
+}
+
+impl NameIndex {
+    /// Whether the component type is one of the indexed ones
+    pub(crate) fn indexes(type_id: &TypeId) -> bool {
+        *type_id == TypeId::of::<Name>() || *type_id == TypeId::of::<Tags>()
+    }
+
+    pub(crate) fn insert(&mut self, entity: Entity, name: Option<&Name>, tags: Option<&Tags>) {
+        if name.is_none() && tags.is_none() {
+            return;
+        }
+        if let Some(name) = name {
+            self.names.entry(name.0.clone()).or_default().push(entity);
+        }
+        for tag in tags.iter().flat_map(|tags| tags.iter()) {
+            self.tags.entry(tag.clone()).or_default().push(entity);
+        }
+        self.indexed.insert(entity, (name.cloned(), tags.cloned()));
+    }
+
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        if let Some((name, tags)) = self.indexed.remove(&entity) {
+            if let Some(name) = name {
+                Self::remove_from(&mut self.names, &name.0, entity);
+            }
+            for tag in tags.iter().flat_map(|tags| tags.iter()) {
+                Self::remove_from(&mut self.tags, tag, entity);
+            }
+        }
+    }
+
+    /// Whether `entity` is indexed with this name and these tags
+    pub(crate) fn is_indexed(&self, entity: Entity, name: Option<&Name>, tags: Option<&Tags>) -> bool {
+        self.indexed
+            .get(&entity)
+            .is_some_and(|(indexed_name, indexed_tags)| indexed_name.as_ref() == name && indexed_tags.as_ref() == tags)
+    }
+
+    pub(crate) fn indexed_entities(&self) -> impl Iterator<Item = &Entity> {
+        self.indexed.keys()
+    }
+
+    pub(crate) fn named(&self, name: &str) -> &[Entity] {
+        self.names.get(name).map_or(&[], |entities| entities.as_slice())
+    }
+
+    pub(crate) fn tagged(&self, tag: &str) -> &[Entity] {
+        self.tags.get(tag).map_or(&[], |entities| entities.as_slice())
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.names.clear();
+        self.tags.clear();
+        self.indexed.clear();
+    }
+
+    fn remove_from(index: &mut HashMap<String, Vec<Entity>>, key: &str, entity: Entity) {
+        if let Some(entities) = index.get_mut(key) {
+            entities.retain(|e| *e != entity);
+            if entities.is_empty() {
+                index.remove(key);
+            }
+        }
+    }
+}
@@ -1,11 +1,17 @@
+pub mod app_state;
 pub(crate) mod audio_controller;
+pub mod change_detection;
 pub mod package;
 pub mod resources;
 pub mod scene;
-pub(crate) mod scheduler;
+pub mod scene_transition;
+pub mod scheduler;
+pub mod snapshot;
 pub mod state;
+pub mod system_param;
 pub mod systems;
 pub mod world;
 pub mod application_builder;
 pub mod scion_runner;
+pub mod test_app;
 pub mod components;
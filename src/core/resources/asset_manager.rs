@@ -1,6 +1,7 @@
 use std::{collections::HashMap, marker::PhantomData};
 
 use log::debug;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::graphics::components::{material::Material, tiles::tileset::Tileset};
 use crate::graphics::components::ui::font::Font;
@@ -125,6 +126,19 @@ pub struct AssetRef<T: Send + Sync>(pub(crate) usize, pub(crate) PhantomData<T>)
 impl Copy for AssetRef<Material>{}
 impl Copy for AssetRef<Font>{}
 
+/// An asset ref is serialized as its index, it is only valid as long as the assets are registered in the same order
+impl<T: Send + Sync> Serialize for AssetRef<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0 as u64)
+    }
+}
+
+impl<'de, T: Send + Sync> Deserialize<'de> for AssetRef<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(AssetRef(usize::deserialize(deserializer)?, PhantomData))
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub enum AssetType {
     Tileset(String),
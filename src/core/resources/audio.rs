@@ -1,33 +1,62 @@
 use std::sync::mpsc;
-
-use rodio::{OutputStream, Sink};
+use std::thread::JoinHandle;
 
 use crate::core::audio_controller;
 use crate::core::audio_controller::AudioController;
 
 /// `AudioPlayer` is the resource responsible to handle musics, sound effects, and action on them
 pub struct Audio {
-    event_sender: mpsc::Sender<AudioEvent>,
+    event_sender: Option<mpsc::Sender<AudioEvent>>,
+    audio_thread: Option<JoinHandle<()>>,
     sounds_cursor: usize,
+    enabled: bool,
 }
 
 impl Audio {
+    /// Creates the audio resource. The audio thread, and so the output device, is only opened
+    /// when the first sound is played.
     pub(crate) fn default() -> Self {
-        let (event_sender, receiver) = mpsc::channel();
+        Audio { event_sender: None, audio_thread: None, sounds_cursor: 0, enabled: true }
+    }
 
-        std::thread::spawn(move || audio_controller::audio_thread(AudioController::new(receiver)));
-        Audio { event_sender, sounds_cursor: 0 }
+    /// Creates an audio resource that never opens any output device. Sounds played through it
+    /// are silently ignored. Used when running headless.
+    pub(crate) fn disabled() -> Self {
+        Audio { event_sender: None, audio_thread: None, sounds_cursor: 0, enabled: false }
     }
 
     /// Start to play the sound identified with `name`
     pub fn play(&mut self, path: String, config: PlayConfig) -> Result<usize, Error> {
         let sound_id = self.sounds_cursor;
-        if let Ok(()) = self.event_sender.send(AudioEvent::PlaySound { path, config, sound_id }) {
+        if !self.enabled {
+            self.sounds_cursor += 1;
+            return Ok(sound_id);
+        }
+        if let Ok(()) = self.sender().send(AudioEvent::PlaySound { path, config, sound_id }) {
             self.sounds_cursor += 1;
             return Ok(sound_id);
         }
         Err(Error::ImpossibleToLoadSound)
     }
+
+    /// Stops all the sounds and waits for the audio thread to end
+    pub(crate) fn shutdown(mut self) {
+        self.event_sender.take();
+        if let Some(audio_thread) = self.audio_thread.take() {
+            let _r = audio_thread.join();
+        }
+    }
+
+    fn sender(&mut self) -> &mpsc::Sender<AudioEvent> {
+        let audio_thread = &mut self.audio_thread;
+        self.event_sender.get_or_insert_with(|| {
+            let (event_sender, receiver) = mpsc::channel();
+            *audio_thread = Some(std::thread::spawn(move || {
+                audio_controller::audio_thread(AudioController::new(receiver))
+            }));
+            event_sender
+        })
+    }
 }
 
 /// Error that can be thrown by the AudioPlayer
@@ -0,0 +1,134 @@
+use hecs::{Component, DynamicBundle, Entity};
+
+use crate::core::world::{GameData, World};
+
+type Command = Box<dyn FnOnce(&mut GameData) + Send + Sync>;
+
+/// `Commands` is a resource queuing modifications of the world, to be able to spawn or despawn entities
+/// and add or remove components while iterating over a query.
+///
+/// The queued commands are applied, in the order they were added, at the end of each [`crate::core::scheduler::Stage`]
+/// and at the end of each frame. Use [`GameData::reserve_entity`] to know the entity that a spawn will create.
+#[derive(Default)]
+pub struct Commands {
+    queue: Vec<Command>,
+}
+
+impl Commands {
+    /// Spawns a new entity with the given components
+    pub fn spawn(&mut self, components: impl DynamicBundle + Send + Sync + 'static) {
+        self.add(move |data| {
+            data.push(components);
+        });
+    }
+
+    /// Despawns `entity`. Ignored if it no longer exists
+    pub fn despawn(&mut self, entity: Entity) {
+        self.add(move |data| {
+            let _r = data.remove(entity);
+        });
+    }
+
+    /// Despawns `entity` and all its descendants, see [`World::remove_recursive`]
+    pub fn despawn_recursive(&mut self, entity: Entity) {
+        self.add(move |data| {
+            let _r = data.remove_recursive(entity);
+        });
+    }
+
+    /// Despawns `entities` and all their descendants, walking the hierarchy once, see [`World::remove_all_recursive`]
+    pub fn despawn_all_recursive(&mut self, entities: Vec<Entity>) {
+        self.add(move |data| data.remove_all_recursive(&entities));
+    }
+
+    /// Adds the given components to `entity`, replacing the ones it already has.
+    /// If `entity` was reserved with [`GameData::reserve_entity`], it is spawned with these components.
+    pub fn insert(&mut self, entity: Entity, components: impl DynamicBundle + Send + Sync + 'static) {
+        self.add(move |data| {
+            let _r = data.add_components(entity, components);
+        });
+    }
+
+    /// Removes the component `T` from `entity`. Ignored if the entity or the component no longer exist
+    pub fn remove<T: Component>(&mut self, entity: Entity) {
+        self.add(move |data| {
+            let _r = data.remove_component::<T>(entity);
+        });
+    }
+
+    /// Queues a custom modification of the game data
+    pub fn add<F: FnOnce(&mut GameData) + Send + Sync + 'static>(&mut self, command: F) {
+        self.queue.push(Box::new(command));
+    }
+
+    /// Whether there is no command waiting to be applied
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+/// Applies the queued commands. Commands queued while applying are applied too.
+pub(crate) fn apply_commands(data: &mut GameData) {
+    loop {
+        let queue = match data.get_resource_mut::<Commands>() {
+            Some(mut commands) if !commands.is_empty() => std::mem::take(&mut commands.queue),
+            _ => return,
+        };
+        queue.into_iter().for_each(|command| command(data));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::components::maths::hierarchy::Parent;
+
+    use super::*;
+
+    fn data() -> GameData {
+        let mut data = GameData::default();
+        data.insert_resource(Commands::default());
+        data
+    }
+
+    #[test]
+    fn commands_applied_in_order_test() {
+        let mut data = data();
+        let entity = data.push((1,));
+        let reserved = data.reserve_entity();
+        for (e, _) in data.query::<&i32>().iter() {
+            let mut commands = data.commands();
+            commands.insert(e, (2u8,));
+            commands.remove::<i32>(e);
+            commands.insert(reserved, (3u8,));
+            commands.spawn((4u8,));
+        }
+        assert!(data.entry::<&u8>(entity).unwrap().get().is_none());
+
+        apply_commands(&mut data);
+        assert_eq!(2, *data.entry::<&u8>(entity).unwrap().get().unwrap());
+        assert!(data.entry::<&i32>(entity).unwrap().get().is_none());
+        assert_eq!(3, *data.entry::<&u8>(reserved).unwrap().get().unwrap());
+        assert_eq!(3, data.query::<&u8>().iter().count());
+        assert!(data.commands().is_empty());
+    }
+
+    #[test]
+    fn despawn_recursive_test() {
+        let mut data = data();
+        let root = data.push((1,));
+        let child = data.push((Parent(root),));
+        let grand_child = data.push((Parent(child),));
+        let other = data.push((2,));
+
+        data.commands().despawn_recursive(root);
+        data.commands().add(move |data| {
+            data.commands().despawn(other);
+        });
+        apply_commands(&mut data);
+
+        assert!(!data.contains(root));
+        assert!(!data.contains(child));
+        assert!(!data.contains(grand_child));
+        assert!(!data.contains(other));
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use downcast_rs::{impl_downcast, Downcast};
+
+/// `ChannelConfiguration` represents the retention of the events of a typed channel
+#[derive(Clone, Debug)]
+pub struct ChannelConfiguration {
+    /// Maximum number of events kept in the channel, the oldest ones are dropped first when it is exceeded
+    pub limit: usize,
+    /// Number of frames an event is kept, `None` to keep it until the limit is exceeded
+    pub frames: Option<u64>,
+}
+
+impl Default for ChannelConfiguration {
+    fn default() -> Self {
+        Self { limit: 256, frames: Some(2) }
+    }
+}
+
+/// Cursor of a reader of the typed channel `channel`, see [`crate::core::resources::events::Events::reader`].
+/// Each reader reads each event once.
+pub struct EventReader<T> {
+    pub(crate) channel: String,
+    pub(crate) cursor: u64,
+    _event: PhantomData<fn() -> T>,
+}
+
+impl<T> EventReader<T> {
+    pub(crate) fn new(channel: &str, cursor: u64) -> Self {
+        Self { channel: channel.to_string(), cursor, _event: PhantomData }
+    }
+
+    /// Name of the channel read by this reader
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+}
+
+pub(crate) trait AnyChannel: Downcast + Send + Sync {
+    fn type_name(&self) -> &'static str;
+
+    /// Drops the events older than the retention, `frame` being the frame that just ended
+    fn cleanup(&mut self, frame: u64);
+}
+impl_downcast!(AnyChannel);
+
+/// Typed channel storing the events as they are sent, each one with its sequence number and frame
+pub(crate) struct Channel<T> {
+    configuration: ChannelConfiguration,
+    events: VecDeque<(u64, u64, T)>,
+    next_sequence: u64,
+}
+
+impl<T: Send + Sync + 'static> Channel<T> {
+    pub(crate) fn new(configuration: ChannelConfiguration) -> Self {
+        Self { configuration, events: VecDeque::new(), next_sequence: 0 }
+    }
+
+    pub(crate) fn send(&mut self, event: T, frame: u64) {
+        self.events.push_back((self.next_sequence, frame, event));
+        self.next_sequence += 1;
+        while self.events.len() > self.configuration.limit {
+            self.events.pop_front();
+        }
+    }
+
+    pub(crate) fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// Events sent since `cursor`, and the cursor following them
+    pub(crate) fn read(&self, cursor: u64) -> (Vec<&T>, u64) {
+        let events = self.events.iter().filter(|(sequence, _, _)| *sequence >= cursor).map(|(_, _, event)| event).collect();
+        (events, self.next_sequence)
+    }
+}
+
+impl<T: Send + Sync + 'static> AnyChannel for Channel<T> {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    fn cleanup(&mut self, frame: u64) {
+        if let Some(frames) = self.configuration.frames {
+            while self.events.front().is_some_and(|(_, sent, _)| frame + 1 - sent >= frames) {
+                self.events.pop_front();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod channel_tests {
+    use super::*;
+
+    #[test]
+    fn channel_retention_test() {
+        let mut channel = Channel::new(ChannelConfiguration { limit: 3, frames: Some(2) });
+        (0..4).for_each(|i| channel.send(i, 0));
+        assert_eq!(vec![&1, &2, &3], channel.read(0).0);
+
+        channel.cleanup(0);
+        channel.send(4, 1);
+        assert_eq!(vec![&2, &3, &4], channel.read(0).0);
+        channel.cleanup(1);
+        assert_eq!((vec![&4], 5), channel.read(0));
+    }
+}
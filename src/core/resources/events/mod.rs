@@ -1,10 +1,17 @@
 use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use log::{error, info, warn};
 
 use serde::{de::DeserializeOwned, ser};
 use serde_json::{from_str, to_string};
 
+use crate::core::resources::events::channel::{AnyChannel, Channel, ChannelConfiguration, EventReader};
+use crate::core::resources::events::recording::{EventRecorder, EventReplayer};
 use crate::core::resources::events::topic::{Topic, TopicConfiguration};
 
+pub mod channel;
+mod recording;
 pub mod topic;
 
 pub type SubscriberId = usize;
@@ -22,19 +29,42 @@ impl Default for PollConfiguration {
     }
 }
 
+impl PollConfiguration {
+    /// Creates a configuration retrieving at most `max_messages` messages per poll
+    pub fn with_max_messages(max_messages: usize) -> Self {
+        Self { max_messages }
+    }
+}
+
 /// `EventError` represents the different error that any event Result can return
 #[derive(Debug)]
 pub enum EventError {
     TopicAlreadyExist,
     TopicDoesNotExist,
     SubscriberIdDoesNotExist,
+    /// The event could not be serialized to be published in a topic
+    SerializationFailed(String),
+    ChannelAlreadyExist,
+    ChannelDoesNotExist,
+    /// The typed channel carries events of type `expected`, not `found`
+    TypeMismatch { expected: &'static str, found: &'static str },
+    /// The recording file could not be created, or the replayed file could not be read
+    RecordingFailed(String),
 }
 
-/// `Events` is a convenience resource to help communicate between systems/resources/layers through events
+/// `Events` is a convenience resource to help communicate between systems/resources/layers through events.
+///
+/// Events are either published in topics, as json messages, or sent in typed channels, which store the events
+/// as they are and don't need them to be serializable.
 #[derive(Default)]
 pub struct Events {
     topics: HashMap<String, Topic>,
     subscribers: HashMap<SubscriberId, (String, PollConfiguration, Cursor)>,
+    channels: HashMap<String, Box<dyn AnyChannel>>,
+    /// Number of frames ended since the start, used for the retention of the typed channels and the recordings
+    frame: u64,
+    recorder: Option<EventRecorder>,
+    replayer: Option<EventReplayer>,
 }
 
 impl Events {
@@ -61,15 +91,84 @@ impl Events {
         if !self.topics.contains_key(topic_name) {
             Err(EventError::TopicDoesNotExist)
         } else {
-            let message = to_string(&event).unwrap();
-            self.topics
-                .get_mut(topic_name)
-                .expect("A topic is missing, but is identified as existing")
-                .publish(message);
+            let message = to_string(&event).map_err(|e| EventError::SerializationFailed(e.to_string()))?;
+            self.publish_message(topic_name, message);
             Ok(())
         }
     }
 
+    fn publish_message(&mut self, topic_name: &str, message: String) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(e) = recorder.record(self.frame, topic_name, &message) {
+                error!("Error while recording the events, the recording is stopped: {}", e);
+                self.recorder = None;
+            }
+        }
+        self.topics
+            .get_mut(topic_name)
+            .expect("A topic is missing, but is identified as existing")
+            .publish(message);
+    }
+
+    /// Starts to record every message published in the topics to the json-lines file at `path`, with the topic and
+    /// the frame of the publication, counted from now. Typed channels are not recorded.
+    pub fn start_recording(&mut self, path: &Path) -> Result<(), EventError> {
+        let recorder = EventRecorder::create(path, self.frame).map_err(|e| EventError::RecordingFailed(e.to_string()))?;
+        self.recorder = Some(recorder);
+        info!("Recording the events to {:?}", path);
+        Ok(())
+    }
+
+    pub fn stop_recording(&mut self) {
+        if let Some(mut recorder) = self.recorder.take() {
+            if let Err(e) = recorder.flush() {
+                error!("Error while writing the events recording: {}", e);
+            }
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Publishes again the messages recorded in the file at `path` by [`Events::start_recording`], each one at the
+    /// same frame as when it was recorded, counted from now.
+    /// The topics must exist when the messages are published, messages of missing topics are dropped.
+    pub fn start_replay(&mut self, path: &Path) -> Result<(), EventError> {
+        let replayer = EventReplayer::load(path, self.frame).map_err(EventError::RecordingFailed)?;
+        self.replayer = Some(replayer);
+        info!("Replaying the events of {:?}", path);
+        Ok(())
+    }
+
+    pub fn stop_replay(&mut self) {
+        self.replayer = None;
+    }
+
+    /// Whether a replay still has messages to publish
+    pub fn is_replaying(&self) -> bool {
+        self.replayer.is_some()
+    }
+
+    /// Publishes the replayed messages of the frame that starts
+    pub(crate) fn start_frame(&mut self) {
+        let due = match self.replayer.as_mut() {
+            Some(replayer) => replayer.due(self.frame),
+            None => return,
+        };
+        for (topic_name, message) in due {
+            if self.topics.contains_key(&topic_name) {
+                self.publish_message(&topic_name, message);
+            } else {
+                warn!("Replayed message dropped, the topic {} does not exist", topic_name);
+            }
+        }
+        if self.replayer.as_ref().is_some_and(|replayer| replayer.is_finished()) {
+            info!("Events replay finished");
+            self.replayer = None;
+        }
+    }
+
     /// Creates a subscription to the topic `topic_name` using `poll_configuration`
     pub fn subscribe(
         &mut self,
@@ -96,7 +195,8 @@ impl Events {
         }
     }
 
-    /// Retrieves a list of events using `subscriber_id` subscription to a topic
+    /// Retrieves a list of events using `subscriber_id` subscription to a topic.
+    /// The messages that can't be deserialized as a `T` are skipped, with a warning.
     pub fn poll<T>(&mut self, subscriber_id: &SubscriberId) -> Result<VecDeque<T>, EventError>
     where
         T: DeserializeOwned,
@@ -118,17 +218,77 @@ impl Events {
                 *cursor + poll_configuration.max_messages
             };
             let target_slice = &topic.messages[slice_start..slice_end];
-            let polled: VecDeque<T> =
-                target_slice.iter().filter_map(|message| from_str(message).ok()).collect();
-            *cursor += polled.len();
+            let polled: VecDeque<T> = target_slice
+                .iter()
+                .filter_map(|message| match from_str(message) {
+                    Ok(event) => Some(event),
+                    Err(e) => {
+                        warn!("Message {} of topic {} skipped, it can't be deserialized : {}", message, topic_name, e);
+                        None
+                    }
+                })
+                .collect();
+            *cursor = slice_end;
             return Ok(polled);
         }
         Err(EventError::SubscriberIdDoesNotExist)
     }
 
+    /// Creates a typed channel `channel_name`, carrying events of type `T`
+    pub fn create_channel<T: Send + Sync + 'static>(
+        &mut self,
+        channel_name: &str,
+        channel_configuration: ChannelConfiguration,
+    ) -> Result<(), EventError> {
+        if self.channels.contains_key(channel_name) {
+            Err(EventError::ChannelAlreadyExist)
+        } else {
+            self.channels.insert(channel_name.to_string(), Box::new(Channel::<T>::new(channel_configuration)));
+            Ok(())
+        }
+    }
+
+    /// Sends an event into the typed channel `channel_name`
+    pub fn send<T: Send + Sync + 'static>(&mut self, channel_name: &str, event: T) -> Result<(), EventError> {
+        let frame = self.frame;
+        let channel = self.channels.get_mut(channel_name).ok_or(EventError::ChannelDoesNotExist)?;
+        let expected = channel.type_name();
+        channel
+            .downcast_mut::<Channel<T>>()
+            .ok_or(EventError::TypeMismatch { expected, found: std::any::type_name::<T>() })?
+            .send(event, frame);
+        Ok(())
+    }
+
+    /// Creates a reader of the typed channel `channel_name`, which will read the events sent from now on
+    pub fn reader<T: Send + Sync + 'static>(&self, channel_name: &str) -> Result<EventReader<T>, EventError> {
+        Ok(EventReader::new(channel_name, self.channel::<T>(channel_name)?.next_sequence()))
+    }
+
+    /// Reads the events sent in the channel of `reader` since its previous read, and still retained by the channel
+    pub fn read<T: Send + Sync + 'static>(&self, reader: &mut EventReader<T>) -> Result<Vec<&T>, EventError> {
+        let (events, cursor) = self.channel::<T>(&reader.channel)?.read(reader.cursor);
+        reader.cursor = cursor;
+        Ok(events)
+    }
+
+    fn channel<T: Send + Sync + 'static>(&self, channel_name: &str) -> Result<&Channel<T>, EventError> {
+        let channel = self.channels.get(channel_name).ok_or(EventError::ChannelDoesNotExist)?;
+        channel
+            .downcast_ref::<Channel<T>>()
+            .ok_or(EventError::TypeMismatch { expected: channel.type_name(), found: std::any::type_name::<T>() })
+    }
+
     pub(crate) fn cleanup(&mut self) {
         self.cleanup_topics_overflow();
-        self.cleanup_topics_outdated()
+        self.cleanup_topics_outdated();
+        let frame = self.frame;
+        self.channels.values_mut().for_each(|channel| channel.cleanup(frame));
+        self.frame += 1;
+        if let Some(Err(e)) = self.recorder.as_mut().map(|recorder| recorder.flush()) {
+            error!("Error while writing the events recording, the recording is stopped: {}", e);
+            self.recorder = None;
+        }
     }
 
     fn cleanup_topics_outdated(&mut self) {
@@ -173,7 +333,7 @@ impl Events {
 
 #[cfg(test)]
 mod event_tests {
-    use crate::core::resources::events::{Events, PollConfiguration, TopicConfiguration};
+    use crate::core::resources::events::{ChannelConfiguration, EventError, Events, PollConfiguration, TopicConfiguration};
 
     #[test]
     fn create_topic_test() {
@@ -236,6 +396,70 @@ mod event_tests {
         assert_eq!(12, poll_result.pop_front().unwrap());
     }
 
+    #[test]
+    fn poll_skips_invalid_messages_test() {
+        let mut event = Events::default();
+        let _r = event.create_topic("test_topic", TopicConfiguration { limit: 100 });
+        let subscriber_id = event.subscribe("test_topic", PollConfiguration::default()).unwrap();
+        let _r = event.publish("test_topic", 4);
+        let _r = event.publish("test_topic", "not a number");
+        let _r = event.publish("test_topic", 8);
+
+        assert_eq!(vec![4, 8], Vec::from(event.poll::<usize>(&subscriber_id).unwrap()));
+        let _r = event.publish("test_topic", 12);
+        assert_eq!(vec![12], Vec::from(event.poll::<usize>(&subscriber_id).unwrap()));
+    }
+
+    #[test]
+    fn typed_channel_test() {
+        let mut event = Events::default();
+        assert!(event.send("entities", hecs::Entity::DANGLING).is_err());
+        let _r = event.create_channel::<hecs::Entity>("entities", ChannelConfiguration::default());
+        assert!(event.create_channel::<u8>("entities", ChannelConfiguration::default()).is_err());
+
+        let _r = event.send("entities", hecs::Entity::DANGLING);
+        let mut reader = event.reader::<hecs::Entity>("entities").unwrap();
+        assert!(matches!(event.send("entities", 1u8), Err(EventError::TypeMismatch { .. })));
+        assert!(matches!(event.reader::<u8>("entities"), Err(EventError::TypeMismatch { .. })));
+
+        let _r = event.send("entities", hecs::Entity::DANGLING);
+        assert_eq!(1, event.read(&mut reader).unwrap().len());
+        assert!(event.read(&mut reader).unwrap().is_empty());
+
+        let _r = event.send("entities", hecs::Entity::DANGLING);
+        event.cleanup();
+        event.cleanup();
+        assert!(event.read(&mut reader).unwrap().is_empty());
+    }
+
+    #[test]
+    fn record_and_replay_test() {
+        let path = std::env::temp_dir().join(format!("scion_events_{}.jsonl", std::process::id()));
+        let mut event = Events::default();
+        let _r = event.create_topic("test_topic", TopicConfiguration::default());
+        event.cleanup();
+        event.start_recording(&path).unwrap();
+        let _r = event.publish("test_topic", 4);
+        event.cleanup();
+        event.cleanup();
+        let _r = event.publish("test_topic", 8);
+        event.stop_recording();
+
+        let mut replayed = Events::default();
+        let _r = replayed.create_topic("test_topic", TopicConfiguration::default());
+        let subscriber_id = replayed.subscribe("test_topic", PollConfiguration::default()).unwrap();
+        replayed.start_replay(&path).unwrap();
+        let mut polled_per_frame = Vec::new();
+        for _ in 0..3 {
+            replayed.start_frame();
+            polled_per_frame.push(Vec::from(replayed.poll::<usize>(&subscriber_id).unwrap()));
+            replayed.cleanup();
+        }
+        assert_eq!(vec![vec![4], vec![], vec![8]], polled_per_frame);
+        assert!(!replayed.is_replaying());
+        let _r = std::fs::remove_file(path);
+    }
+
     #[test]
     fn cleanup_test() {
         let mut event = Events::default();
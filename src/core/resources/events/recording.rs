@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A line of a recording file
+#[derive(Serialize, Deserialize)]
+struct RecordedEvent {
+    /// Frame of the publication, counted from the start of the recording
+    frame: u64,
+    topic: String,
+    payload: Value,
+}
+
+/// Writes the messages published in the topics to a json-lines file
+pub(crate) struct EventRecorder {
+    writer: BufWriter<File>,
+    start_frame: u64,
+}
+
+impl EventRecorder {
+    pub(crate) fn create(path: &Path, start_frame: u64) -> io::Result<Self> {
+        Ok(Self { writer: BufWriter::new(File::create(path)?), start_frame })
+    }
+
+    pub(crate) fn record(&mut self, frame: u64, topic: &str, message: &str) -> io::Result<()> {
+        let payload = serde_json::from_str(message).unwrap_or_else(|_| Value::String(message.to_string()));
+        let event = RecordedEvent { frame: frame - self.start_frame, topic: topic.to_string(), payload };
+        serde_json::to_writer(&mut self.writer, &event)?;
+        self.writer.write_all(b"\n")
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Messages read from a recording file, waiting for their frame to be published again
+pub(crate) struct EventReplayer {
+    events: VecDeque<RecordedEvent>,
+    start_frame: u64,
+}
+
+impl EventReplayer {
+    pub(crate) fn load(path: &Path, start_frame: u64) -> Result<Self, String> {
+        let file = File::open(path).map_err(|e| e.to_string())?;
+        let mut events = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| e.to_string())?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event: RecordedEvent =
+                serde_json::from_str(&line).map_err(|e| format!("line {}: {}", index + 1, e))?;
+            events.push(event);
+        }
+        events.sort_by_key(|event| event.frame);
+        Ok(Self { events: events.into(), start_frame })
+    }
+
+    /// Topics and messages to publish during `frame`, in their recording order
+    pub(crate) fn due(&mut self, frame: u64) -> Vec<(String, String)> {
+        let mut due = Vec::new();
+        while self.events.front().is_some_and(|event| self.start_frame + event.frame <= frame) {
+            let event = self.events.pop_front().expect("A due event is missing");
+            due.push((event.topic, event.payload.to_string()));
+        }
+        due
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
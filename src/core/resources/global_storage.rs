@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 #[derive(Default)]
 pub(crate) struct GlobalStorage{
-    pub(crate) flags : HashMap<String, bool>
+    pub(crate) flags : HashMap<String, bool>,
+    pub(crate) quit_requested: bool,
 }
 
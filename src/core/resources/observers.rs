@@ -0,0 +1,153 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use hecs::{Component, Entity};
+use log::warn;
+
+use crate::core::world::GameData;
+
+type Observer = Arc<Mutex<dyn FnMut(&mut GameData, Entity) + Send>>;
+
+/// `Observers` is a resource holding the callbacks executed when a component type is added to or removed from
+/// an entity, or when an entity is removed.
+///
+/// Callbacks are executed right away by the [`crate::core::world::World`] functions of `GameData`, including the ones
+/// used to apply the [`crate::core::resources::commands::Commands`]. Removal callbacks are executed before the removal,
+/// so that they can still read the entity's components. Modifications made through the `SubWorld` obtained with
+/// [`GameData::split`] are not observed.
+///
+/// The observers of `Scion` itself are stored apart, replacing this resource doesn't remove them.
+///
+/// A callback can modify the world, but its own modifications don't execute it again.
+#[derive(Default)]
+pub struct Observers {
+    on_add: HashMap<TypeId, Vec<Observer>>,
+    on_remove: HashMap<TypeId, Vec<Observer>>,
+    on_despawn: Vec<Observer>,
+}
+
+impl Observers {
+    /// Registers a callback executed each time a `T` is added to an entity which didn't have one
+    pub fn on_add<T: Component, F: FnMut(&mut GameData, Entity) + Send + 'static>(&mut self, callback: F) {
+        self.on_add.entry(TypeId::of::<T>()).or_default().push(Arc::new(Mutex::new(callback)));
+    }
+
+    /// Registers a callback executed each time a `T` is about to be removed from an entity, including when the entity
+    /// is removed
+    pub fn on_remove<T: Component, F: FnMut(&mut GameData, Entity) + Send + 'static>(&mut self, callback: F) {
+        self.on_remove.entry(TypeId::of::<T>()).or_default().push(Arc::new(Mutex::new(callback)));
+    }
+
+    /// Registers a callback executed each time an entity is about to be removed
+    pub fn on_despawn<F: FnMut(&mut GameData, Entity) + Send + 'static>(&mut self, callback: F) {
+        self.on_despawn.push(Arc::new(Mutex::new(callback)));
+    }
+
+    fn is_empty(&self) -> bool {
+        self.on_add.is_empty() && self.on_remove.is_empty() && self.on_despawn.is_empty()
+    }
+
+    fn callbacks(&self, lifecycle: &Lifecycle) -> Vec<Observer> {
+        match lifecycle {
+            Lifecycle::Added(types) => types.iter().filter_map(|t| self.on_add.get(t)).flatten().cloned().collect(),
+            Lifecycle::Removed(types) => types.iter().filter_map(|t| self.on_remove.get(t)).flatten().cloned().collect(),
+            Lifecycle::Despawned => self.on_despawn.clone(),
+        }
+    }
+}
+
+/// Observers registered by `Scion` itself, executed before the ones of the [`Observers`] resource
+#[derive(Default)]
+pub(crate) struct EngineObservers(pub(crate) Observers);
+
+/// Lifecycle step that triggers observers
+pub(crate) enum Lifecycle<'a> {
+    Added(&'a [TypeId]),
+    Removed(&'a [TypeId]),
+    Despawned,
+}
+
+/// Whether some observers are registered, to avoid looking for the observed types when there are none
+pub(crate) fn observed(data: &GameData) -> bool {
+    data.get_resource::<EngineObservers>().is_some_and(|observers| !observers.0.is_empty())
+        || data.get_resource::<Observers>().is_some_and(|observers| !observers.is_empty())
+}
+
+/// Executes the observers of `lifecycle` on `entity`
+pub(crate) fn notify(data: &mut GameData, entity: Entity, lifecycle: Lifecycle) {
+    let mut callbacks =
+        data.get_resource::<EngineObservers>().map(|observers| observers.0.callbacks(&lifecycle)).unwrap_or_default();
+    if let Some(observers) = data.get_resource::<Observers>() {
+        callbacks.extend(observers.callbacks(&lifecycle));
+    }
+    for callback in callbacks {
+        match callback.try_lock() {
+            Ok(mut callback) => callback(data, entity),
+            Err(_) => warn!(
+                "An observer was triggered by its own modifications on entity {}, it is skipped",
+                data.entity_label(entity)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::world::World;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    struct Sound(&'static str);
+
+    fn log(data: &GameData, line: String) {
+        data.get_resource_mut::<Log>().unwrap().0.push(line);
+    }
+
+    #[test]
+    fn observers_are_notified_test() {
+        let mut data = GameData::default();
+        data.insert_resource(Log::default());
+        let mut observers = Observers::default();
+        observers.on_add::<Sound, _>(|data, _| log(data, "add".to_string()));
+        observers.on_remove::<Sound, _>(|data, entity| {
+            // The component is still readable
+            let sound = data.entry::<&Sound>(entity).unwrap().get().unwrap().0;
+            log(data, format!("remove {}", sound));
+        });
+        observers.on_despawn(|data, _| log(data, "despawn".to_string()));
+        data.insert_resource(observers);
+
+        let first = data.push((Sound("music"),));
+        let second = data.push((1,));
+        let _r = data.add_components(second, (Sound("jump"),));
+        let _r = data.add_components(second, (Sound("land"),));
+        let _r = data.remove_component::<Sound>(second);
+        let _r = data.remove(first);
+        let _r = data.remove(second);
+
+        assert_eq!(
+            vec!["add", "add", "remove land", "despawn", "remove music", "despawn"],
+            data.get_resource::<Log>().unwrap().0
+        );
+    }
+
+    #[test]
+    fn observers_can_modify_the_world_test() {
+        let mut data = GameData::default();
+        let mut observers = Observers::default();
+        observers.on_add::<Sound, _>(|data, entity| {
+            let _r = data.add_components(entity, (2u8,));
+            // Doesn't trigger this observer again
+            data.push((Sound("echo"),));
+        });
+        data.insert_resource(observers);
+
+        let entity = data.push((Sound("music"),));
+        assert!(data.entry::<&u8>(entity).unwrap().get().is_some());
+        assert_eq!(2, data.query::<&Sound>().iter().count());
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::core::world::GameData;
+
+/// Thread id used in the traces for the game loop thread
+pub(crate) const MAIN_THREAD: usize = 0;
+/// Thread id used in the traces for the rendering thread
+pub(crate) const RENDER_THREAD: usize = 1;
+/// First thread id used in the traces for the systems executed in parallel
+pub(crate) const FIRST_WORKER_THREAD: usize = 2;
+
+const DEFAULT_MAX_SPANS: usize = 100_000;
+
+/// Kind of work measured by a [`ProfileSpan`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ProfileCategory {
+    /// Execution of a system, internal or registered by the game or a scene
+    System,
+    /// Execution of a scene hook like `on_update` or `late_update`
+    SceneHook,
+    /// Preparation of the rendering updates or rendering itself
+    Render,
+}
+
+impl ProfileCategory {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ProfileCategory::System => "system",
+            ProfileCategory::SceneHook => "scene",
+            ProfileCategory::Render => "render",
+        }
+    }
+}
+
+/// A single measured execution
+#[derive(Debug, Clone)]
+pub struct ProfileSpan {
+    pub name: String,
+    pub category: ProfileCategory,
+    /// Thread on which the work was executed : 0 for the game loop, 1 for the rendering thread,
+    /// 2 and more for the systems executed in parallel
+    pub thread: usize,
+    /// Start of the execution, relative to the moment the profiler was enabled
+    pub start: Duration,
+    pub duration: Duration,
+}
+
+/// Aggregated timings of everything recorded with the same name
+#[derive(Debug, Clone, Default)]
+pub struct ProfileStats {
+    calls: u64,
+    total: Duration,
+    last: Duration,
+    max: Duration,
+}
+
+impl ProfileStats {
+    /// Number of recorded executions
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    /// Cumulated duration of all the recorded executions
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// Duration of the last recorded execution
+    pub fn last(&self) -> Duration {
+        self.last
+    }
+
+    /// Longest recorded execution
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Average duration of an execution
+    pub fn average(&self) -> Duration {
+        if self.calls == 0 {
+            Duration::ZERO
+        } else {
+            self.total.div_f64(self.calls as f64)
+        }
+    }
+}
+
+/// `Profiler` is a resource recording how long the systems, scene hooks and rendering phases take.
+/// It is disabled by default, enable it with [`Profiler::enable`] or [`crate::ScionBuilder::with_profiler`].
+///
+/// Timings are aggregated by name in [`ProfileStats`], queryable in game, and the last spans are kept to be
+/// exported as a Chrome trace, that can be opened in `chrome://tracing` or <https://ui.perfetto.dev>.
+pub struct Profiler {
+    enabled: bool,
+    origin: Instant,
+    max_spans: usize,
+    spans: VecDeque<ProfileSpan>,
+    stats: HashMap<String, ProfileStats>,
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            origin: Instant::now(),
+            max_spans: DEFAULT_MAX_SPANS,
+            spans: VecDeque::new(),
+            stats: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ChromeTrace<'a> {
+    #[serde(rename = "traceEvents")]
+    trace_events: Vec<ChromeTraceEvent<'a>>,
+}
+
+#[derive(Serialize)]
+struct ChromeTraceEvent<'a> {
+    name: &'a str,
+    cat: &'a str,
+    ph: &'a str,
+    /// Start in microseconds
+    ts: f64,
+    /// Duration in microseconds
+    dur: f64,
+    pid: u32,
+    tid: usize,
+}
+
+impl Profiler {
+    /// Starts recording the timings
+    pub fn enable(&mut self) {
+        if !self.enabled {
+            self.enabled = true;
+            self.origin = Instant::now();
+        }
+    }
+
+    /// Stops recording the timings, what has already been recorded is kept
+    pub fn disable(&mut self) {
+        self.enabled = false;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Sets the number of spans kept for the trace export, the oldest ones are dropped first. Default is 100 000.
+    pub fn set_max_spans(&mut self, max_spans: usize) {
+        self.max_spans = max_spans;
+        self.truncate();
+    }
+
+    /// Aggregated timings of everything recorded with `name`. Systems are named after their label if no other system
+    /// shares it, their type otherwise, like `scion::core::systems::collider_systems::collider_pivot_propagation_system<..>`
+    /// for the instances of a generic internal system. Scene hooks are named `SceneName::hook`.
+    pub fn stats(&self, name: &str) -> Option<&ProfileStats> {
+        self.stats.get(name)
+    }
+
+    /// Aggregated timings of everything recorded so far
+    pub fn all_stats(&self) -> impl Iterator<Item = (&String, &ProfileStats)> {
+        self.stats.iter()
+    }
+
+    /// Spans kept for the trace export, from the oldest to the newest
+    pub fn spans(&self) -> impl Iterator<Item = &ProfileSpan> {
+        self.spans.iter()
+    }
+
+    /// Removes all the recorded spans and stats
+    pub fn clear(&mut self) {
+        self.spans.clear();
+        self.stats.clear();
+    }
+
+    /// Converts the kept spans to the Chrome trace event JSON format
+    pub fn chrome_trace(&self) -> String {
+        let trace = ChromeTrace {
+            trace_events: self
+                .spans
+                .iter()
+                .map(|span| ChromeTraceEvent {
+                    name: &span.name,
+                    cat: span.category.as_str(),
+                    ph: "X",
+                    ts: span.start.as_nanos() as f64 / 1000.,
+                    dur: span.duration.as_nanos() as f64 / 1000.,
+                    pid: 1,
+                    tid: span.thread,
+                })
+                .collect(),
+        };
+        serde_json::to_string(&trace).expect("A chrome trace can always be serialized")
+    }
+
+    /// Writes the Chrome trace of the kept spans to `path`
+    pub fn export_chrome_trace(&self, path: &Path) -> std::io::Result<()> {
+        let mut file = File::create(path)?;
+        file.write_all(self.chrome_trace().as_bytes())
+    }
+
+    /// Records an execution that started at `start`. Ignored if the profiler is disabled.
+    pub(crate) fn record(&mut self, name: &str, category: ProfileCategory, thread: usize, start: Instant, duration: Duration) {
+        if !self.enabled {
+            return;
+        }
+        if !self.stats.contains_key(name) {
+            self.stats.insert(name.to_string(), ProfileStats::default());
+        }
+        let stats = self.stats.get_mut(name).expect("Stats have just been inserted");
+        stats.calls += 1;
+        stats.total += duration;
+        stats.last = duration;
+        stats.max = stats.max.max(duration);
+
+        self.spans.push_back(ProfileSpan {
+            name: name.to_string(),
+            category,
+            thread,
+            start: start.saturating_duration_since(self.origin),
+            duration,
+        });
+        self.truncate();
+    }
+
+    fn truncate(&mut self) {
+        while self.spans.len() > self.max_spans {
+            self.spans.pop_front();
+        }
+    }
+}
+
+/// Whether the profiler resource exists and is enabled
+pub(crate) fn profiling(data: &GameData) -> bool {
+    data.get_resource::<Profiler>().is_some_and(|profiler| profiler.is_enabled())
+}
+
+/// Records an execution in the profiler resource, if it exists and is enabled
+pub(crate) fn record(data: &GameData, name: &str, category: ProfileCategory, thread: usize, start: Instant, duration: Duration) {
+    if let Some(mut profiler) = data.get_resource_mut::<Profiler>() {
+        profiler.record(name, category, thread, start, duration);
+    }
+}
+
+/// Executes `function` on the game loop thread, measuring it if the profiler is enabled.
+/// `name` is only computed when the execution is recorded.
+pub(crate) fn profile<R>(
+    data: &mut GameData,
+    category: ProfileCategory,
+    name: impl FnOnce() -> String,
+    function: impl FnOnce(&mut GameData) -> R,
+) -> R {
+    if !profiling(data) {
+        return function(data);
+    }
+    let start = Instant::now();
+    let result = function(data);
+    record(data, &name(), category, MAIN_THREAD, start, start.elapsed());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_profiler_records_nothing_test() {
+        let mut profiler = Profiler::default();
+        profiler.record("system", ProfileCategory::System, MAIN_THREAD, Instant::now(), Duration::from_millis(2));
+        assert!(profiler.stats("system").is_none());
+        assert_eq!(0, profiler.spans().count());
+    }
+
+    #[test]
+    fn stats_and_span_limit_test() {
+        let mut profiler = Profiler::default();
+        profiler.enable();
+        profiler.set_max_spans(2);
+        for millis in [2, 6, 4] {
+            profiler.record("system", ProfileCategory::System, MAIN_THREAD, Instant::now(), Duration::from_millis(millis));
+        }
+
+        let stats = profiler.stats("system").unwrap();
+        assert_eq!(3, stats.calls());
+        assert_eq!(Duration::from_millis(12), stats.total());
+        assert_eq!(Duration::from_millis(4), stats.last());
+        assert_eq!(Duration::from_millis(6), stats.max());
+        assert_eq!(Duration::from_millis(4), stats.average());
+        assert_eq!(2, profiler.spans().count());
+    }
+
+    #[test]
+    fn chrome_trace_test() {
+        let mut profiler = Profiler::default();
+        profiler.enable();
+        let start = profiler.origin + Duration::from_millis(1);
+        profiler.record("render", ProfileCategory::Render, RENDER_THREAD, start, Duration::from_micros(250));
+
+        let trace: serde_json::Value = serde_json::from_str(&profiler.chrome_trace()).unwrap();
+        let event = &trace["traceEvents"][0];
+        assert_eq!("render", event["name"]);
+        assert_eq!("render", event["cat"]);
+        assert_eq!("X", event["ph"]);
+        assert_eq!(1000., event["ts"]);
+        assert_eq!(250., event["dur"]);
+        assert_eq!(1, event["tid"]);
+    }
+}
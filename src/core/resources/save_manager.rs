@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::snapshot::WorldSnapshot;
+use crate::core::world::GameData;
+use crate::utils::file::{read_file, read_file_modification_time, user_data_path, write_file_atomically};
+use crate::utils::ScionError;
+
+const SAVE_EXTENSION: &str = "save";
+
+type Migration = Box<dyn Fn(&mut Value) -> Result<(), ScionError> + Send + Sync>;
+
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    snapshot: Value,
+}
+
+/// `SaveManager` is the resource storing [`WorldSnapshot`]s in named save slots, one file per slot in the `saves`
+/// directory of the per-user data directory of the application (see [`user_data_path`]).
+///
+/// Each save is stamped with the save version of the game. When the game changes the components or resources it
+/// registers in the [`crate::core::snapshot::SnapshotRegistry`], it increases the version and adds a migration
+/// upgrading the older saves, see [`SaveManager::add_migration`].
+pub struct SaveManager {
+    directory: PathBuf,
+    version: u32,
+    migrations: HashMap<u32, Migration>,
+    auto_save_slot: Option<String>,
+}
+
+impl SaveManager {
+    /// Creates a save manager storing the saves of the application `app_name` in its per-user data directory
+    pub fn new(app_name: &str) -> Self {
+        Self::in_directory(user_data_path(app_name).join("saves"))
+    }
+
+    /// Creates a save manager storing the saves in `directory`
+    pub fn in_directory(directory: impl Into<PathBuf>) -> Self {
+        Self { directory: directory.into(), version: 0, migrations: HashMap::new(), auto_save_slot: None }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Current save version of the game, written in each save. Defaults to 0
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    /// Adds the migration upgrading a save from `from_version` to `from_version + 1`. When a save is loaded, the
+    /// migrations are chained until the current version is reached.
+    ///
+    /// The migration receives the json representation of the snapshot :
+    /// `{"entities": [{"id": 1, "components": {"<name>": <component>}}], "resources": {"<name>": <resource>}}`
+    pub fn add_migration<F>(&mut self, from_version: u32, migration: F)
+    where
+        F: Fn(&mut Value) -> Result<(), ScionError> + Send + Sync + 'static,
+    {
+        self.migrations.insert(from_version, Box::new(migration));
+    }
+
+    /// Saves the game in `slot` when the application shuts down
+    pub fn enable_auto_save(&mut self, slot: &str) {
+        self.auto_save_slot = Some(slot.to_string());
+    }
+
+    pub fn disable_auto_save(&mut self) {
+        self.auto_save_slot = None;
+    }
+
+    /// Writes `snapshot` in `slot`, replacing its previous content.
+    /// A slot name is made of ascii letters, digits, `-` and `_`.
+    pub fn write(&self, slot: &str, snapshot: &WorldSnapshot) -> Result<(), ScionError> {
+        let path = self.slot_path(slot)?;
+        let snapshot = serde_json::to_value(snapshot).map_err(|e| ScionError::new(&e.to_string()))?;
+        let bytes = serde_json::to_vec(&SaveFile { version: self.version, snapshot })
+            .map_err(|e| ScionError::new(&e.to_string()))?;
+        write_file_atomically(&path, &bytes)
+            .map_err(|e| ScionError::new(&format!("Impossible to write the save slot `{}`: {}", slot, e)))
+    }
+
+    /// Reads the snapshot saved in `slot`, migrated to the current version
+    pub fn read(&self, slot: &str) -> Result<WorldSnapshot, ScionError> {
+        let path = self.slot_path(slot)?;
+        let bytes = read_file(&path)
+            .map_err(|e| ScionError::new(&format!("Impossible to read the save slot `{}`: {:?}", slot, e)))?;
+        let mut save: SaveFile = serde_json::from_slice(&bytes)
+            .map_err(|e| ScionError::new(&format!("The save slot `{}` is corrupted: {}", slot, e)))?;
+        if save.version > self.version {
+            return Err(ScionError::new(&format!(
+                "The save slot `{}` has version {}, which is newer than the game version {}",
+                slot, save.version, self.version
+            )));
+        }
+        while save.version < self.version {
+            let migration = self.migrations.get(&save.version).ok_or_else(|| {
+                ScionError::new(&format!("No migration from the save version {}", save.version))
+            })?;
+            migration(&mut save.snapshot)?;
+            save.version += 1;
+        }
+        serde_json::from_value(save.snapshot)
+            .map_err(|e| ScionError::new(&format!("The save slot `{}` is corrupted: {}", slot, e)))
+    }
+
+    /// Whether something is saved in `slot`
+    pub fn exists(&self, slot: &str) -> bool {
+        self.slot_path(slot).is_ok_and(|path| path.is_file())
+    }
+
+    /// Last time `slot` was saved
+    pub fn saved_at(&self, slot: &str) -> Result<SystemTime, ScionError> {
+        read_file_modification_time(&self.slot_path(slot)?)
+            .map_err(|e| ScionError::new(&format!("Impossible to read the save slot `{}`: {:?}", slot, e)))
+    }
+
+    pub fn delete(&self, slot: &str) -> Result<(), ScionError> {
+        fs::remove_file(self.slot_path(slot)?)
+            .map_err(|e| ScionError::new(&format!("Impossible to delete the save slot `{}`: {}", slot, e)))
+    }
+
+    /// Names of the slots containing a save, in alphabetical order
+    pub fn slots(&self) -> Result<Vec<String>, ScionError> {
+        let entries = match fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(ScionError::new(&format!("Impossible to list the save slots: {}", e))),
+        };
+        let mut slots: Vec<String> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|extension| extension == SAVE_EXTENSION))
+            .filter_map(|path| path.file_stem().and_then(|stem| stem.to_str()).map(|stem| stem.to_string()))
+            .collect();
+        slots.sort();
+        Ok(slots)
+    }
+
+    fn slot_path(&self, slot: &str) -> Result<PathBuf, ScionError> {
+        if slot.is_empty() || !slot.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(ScionError::new(&format!("Invalid save slot name `{}`", slot)));
+        }
+        Ok(self.directory.join(format!("{}.{}", slot, SAVE_EXTENSION)))
+    }
+}
+
+/// Saves the game in the auto-save slot, if there is one
+pub(crate) fn auto_save(data: &GameData) {
+    let slot = match data.get_resource::<SaveManager>().and_then(|manager| manager.auto_save_slot.clone()) {
+        Some(slot) => slot,
+        None => return,
+    };
+    match data.save_game(&slot) {
+        Ok(()) => info!("Game auto-saved in slot `{}`", slot),
+        Err(e) => error!("Error while auto-saving the game: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::core::components::name::Name;
+    use crate::core::world::World;
+
+    use super::*;
+
+    static DIRECTORY_ID: AtomicUsize = AtomicUsize::new(0);
+
+    fn data() -> GameData {
+        let directory = std::env::temp_dir().join(format!(
+            "scion_saves_{}_{}",
+            std::process::id(),
+            DIRECTORY_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _r = fs::remove_dir_all(&directory);
+        let mut data = GameData::default();
+        data.insert_resource(SaveManager::in_directory(directory));
+        data
+    }
+
+    #[test]
+    fn save_and_load_slots_test() {
+        let mut data = data();
+        assert!(data.save_manager().slots().unwrap().is_empty());
+        data.push((Name::new("hero"),));
+        data.save_game("slot_1").unwrap();
+        data.push((Name::new("villain"),));
+        data.save_game("slot_2").unwrap();
+
+        assert_eq!(vec!["slot_1", "slot_2"], data.save_manager().slots().unwrap());
+        assert!(data.save_manager().saved_at("slot_1").is_ok());
+        assert!(data.save_game("../outside").is_err());
+
+        data.load_game("slot_1").unwrap();
+        assert_eq!(1, data.entities().len());
+        assert!(data.find_by_name("hero").is_some());
+
+        data.save_manager().delete("slot_1").unwrap();
+        assert!(!data.save_manager().exists("slot_1"));
+        assert!(data.load_game("slot_1").is_err());
+        let _r = fs::remove_dir_all(data.save_manager().directory());
+    }
+
+    #[test]
+    fn app_name_stays_in_data_directory_test() {
+        let data_directory = user_data_path("scion");
+        let data_directory = data_directory.parent().unwrap();
+        for app_name in ["../../outside", "/absolute", "..", "", "C:\\game"] {
+            let directory = SaveManager::new(app_name).directory().to_path_buf();
+            assert_eq!(Some(data_directory), directory.parent().and_then(|app| app.parent()));
+            assert!(!directory.components().any(|component| component == std::path::Component::ParentDir));
+        }
+        assert!(SaveManager::new("My Game").directory().ends_with("My Game/saves"));
+    }
+
+    #[test]
+    fn migrations_test() {
+        let mut data = data();
+        data.push((Name::new("hero"),));
+        data.save_game("slot").unwrap();
+
+        data.save_manager().set_version(2);
+        data.save_manager().add_migration(0, |_| Ok(()));
+        assert!(data.load_game("slot").is_err());
+
+        data.save_manager().add_migration(1, |snapshot| {
+            snapshot["entities"][0]["components"]["name"] = Value::String("knight".to_string());
+            Ok(())
+        });
+        data.load_game("slot").unwrap();
+        assert!(data.find_by_name("knight").is_some());
+
+        // Saves from a newer game version can't be read
+        data.save_game("slot").unwrap();
+        data.save_manager().set_version(1);
+        assert!(data.load_game("slot").is_err());
+        let _r = fs::remove_dir_all(data.save_manager().directory());
+    }
+}
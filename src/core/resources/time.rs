@@ -5,6 +5,8 @@ pub use timer::*;
 pub enum Error {
     TimerAlreadyExists,
     TimerDoesNotExist,
+    /// The duration of a timer must be a positive number of seconds
+    InvalidDuration,
 }
 
 mod time {
@@ -14,6 +16,8 @@ mod time {
     /// frame numbers
     pub struct Time {
         delta_duration: Duration,
+        fixed_delta_duration: Duration,
+        interpolation_alpha: f32,
         frame_number: u64,
         measure_start: Instant,
     }
@@ -22,6 +26,8 @@ mod time {
         fn default() -> Self {
             Self {
                 delta_duration: Default::default(),
+                fixed_delta_duration: Duration::from_secs(1) / 60,
+                interpolation_alpha: 0.,
                 frame_number: 0,
                 measure_start: Instant::now(),
             }
@@ -31,9 +37,15 @@ mod time {
     impl Time {
         /// finish the last frame and return its duration
         pub(crate) fn frame(&mut self) -> Duration {
-            self.frame_number += 1;
-            self.delta_duration = self.measure_start.elapsed();
+            let elapsed = self.measure_start.elapsed();
             self.measure_start = Instant::now();
+            self.frame_with_duration(elapsed)
+        }
+
+        /// finish the last frame using the given duration instead of the measured one
+        pub(crate) fn frame_with_duration(&mut self, duration: Duration) -> Duration {
+            self.frame_number += 1;
+            self.delta_duration = duration;
             self.delta_duration
         }
 
@@ -41,15 +53,47 @@ mod time {
         pub fn delta_duration(&self) -> Duration {
             self.delta_duration
         }
+
+        /// Returns the duration simulated by each fixed update
+        pub fn fixed_delta_duration(&self) -> Duration {
+            self.fixed_delta_duration
+        }
+
+        /// Returns how far the current frame is between the last fixed update and the next one, between 0 and 1.
+        /// Useful to interpolate what is moved during fixed updates.
+        pub fn interpolation_alpha(&self) -> f32 {
+            self.interpolation_alpha
+        }
+
+        pub(crate) fn set_fixed_delta_duration(&mut self, fixed_delta_duration: Duration) {
+            self.fixed_delta_duration = fixed_delta_duration;
+        }
+
+        pub(crate) fn set_interpolation_alpha(&mut self, interpolation_alpha: f32) {
+            self.interpolation_alpha = interpolation_alpha;
+        }
+
+        /// Returns the number of frames executed since the start of the game
+        pub fn frame_number(&self) -> u64 {
+            self.frame_number
+        }
     }
 }
 
 mod timer {
-    use std::{collections::HashMap, time::Duration};
+    use std::{
+        collections::{BTreeMap, HashSet},
+        time::Duration,
+    };
+
+    use log::error;
+    use serde::{Deserialize, Serialize};
 
     use crate::core::resources::time::Error;
+    use crate::core::world::GameData;
 
     /// Different types of timer that car be used
+    #[derive(Serialize, Deserialize)]
     pub enum TimerType {
         /// Manual timers are meant to be launched manually each time.
         /// Once finished, it will wait the next user trigger to restart.
@@ -59,6 +103,9 @@ mod timer {
         Cyclic,
     }
 
+    /// A timer notifies when it ends if it is `Manual`, or each time it completes a cycle if it is `Cyclic`. The callback given to
+    /// [`Timer::with_callback`] is not part of the world snapshots, unlike the topic given to [`Timer::with_topic`].
+    #[derive(Serialize, Deserialize)]
     pub struct Timer {
         /// Type of the current timer
         timer_type: TimerType,
@@ -72,6 +119,20 @@ mod timer {
         dirty: bool,
         /// Total cycles since last cycle fn call
         current_elapsed_cycles: usize,
+        /// A paused timer keeps its elapsed time until it is resumed
+        #[serde(default)]
+        paused: bool,
+        /// Group of the timer, see [`Timers::pause_group`]
+        #[serde(default)]
+        group: Option<String>,
+        /// Event topic in which the name of the timer is published at each notification
+        #[serde(default)]
+        topic: Option<String>,
+        #[serde(skip)]
+        callback: Option<fn(&mut GameData)>,
+        /// Notifications of the last added duration
+        #[serde(skip)]
+        notifications: usize,
     }
 
     impl Timer {
@@ -84,6 +145,11 @@ mod timer {
                 total_duration,
                 dirty: false,
                 current_elapsed_cycles: 0,
+                paused: false,
+                group: None,
+                topic: None,
+                callback: None,
+                notifications: 0,
             }
         }
 
@@ -91,7 +157,8 @@ mod timer {
         /// done a cycle
         pub fn add_delta_duration(&mut self, delta_duration: f32) -> bool {
             self.dirty = false;
-            if !self.running {
+            self.notifications = 0;
+            if !self.running || self.paused {
                 return false;
             }
 
@@ -101,14 +168,19 @@ mod timer {
                     if self.current_duration >= self.total_duration {
                         self.running = false;
                         self.dirty = true;
+                        self.notifications += 1;
                     }
                 }
+                // A cycle without duration would complete endlessly, it never completes instead
+                TimerType::Cyclic if self.total_duration <= 0. => {}
                 TimerType::Cyclic => {
                     let total = self.current_duration + delta_duration;
                     if total > self.total_duration {
                         self.dirty = true;
                     }
-                    self.current_elapsed_cycles += (total / self.total_duration) as usize;
+                    let cycles = (total / self.total_duration) as usize;
+                    self.current_elapsed_cycles = self.current_elapsed_cycles.saturating_add(cycles);
+                    self.notifications += cycles;
                     self.current_duration = total % self.total_duration;
                 }
             }
@@ -121,11 +193,68 @@ mod timer {
             self.current_duration
         }
 
+        /// returns the time left before the timer ends, or before the end of the current cycle
+        pub fn remaining(&self) -> f32 {
+            if self.ended() {
+                0.
+            } else {
+                (self.total_duration - self.current_duration).max(0.)
+            }
+        }
+
+        /// returns the progress of the timer, or of the current cycle, between 0 and 1
+        pub fn progress(&self) -> f32 {
+            if self.ended() || self.total_duration <= 0. {
+                1.
+            } else {
+                (self.current_duration / self.total_duration).clamp(0., 1.)
+            }
+        }
+
         /// returns whether or not the timer has ended
         pub fn ended(&self) -> bool {
             !self.running
         }
 
+        /// pauses the timer, its elapsed time is kept until it is resumed
+        pub fn pause(&mut self) {
+            self.paused = true;
+        }
+
+        pub fn resume(&mut self) {
+            self.paused = false;
+        }
+
+        /// returns whether or not the timer itself is paused. A timer of a paused group is not paused itself,
+        /// see [`Timers::is_group_paused`]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// returns the group of the timer
+        pub fn group(&self) -> Option<&str> {
+            self.group.as_deref()
+        }
+
+        /// adds the timer to `group`, so that it is paused and resumed with it
+        pub fn in_group(&mut self, group: &str) -> &mut Self {
+            self.group = Some(group.to_string());
+            self
+        }
+
+        /// publishes the name of the timer in the event topic `topic` each time it ends or completes a cycle.
+        /// The topic must have been created in the [`crate::core::resources::events::Events`].
+        pub fn with_topic(&mut self, topic: &str) -> &mut Self {
+            self.topic = Some(topic.to_string());
+            self
+        }
+
+        /// executes `callback` each time the timer ends or completes a cycle
+        pub fn with_callback(&mut self, callback: fn(&mut GameData)) -> &mut Self {
+            self.callback = Some(callback);
+            self
+        }
+
         /// reset the timer end start it
         pub fn reset(&mut self) {
             self.running = true;
@@ -134,7 +263,7 @@ mod timer {
             self.current_elapsed_cycles = 0;
         }
 
-        /// changes the total duration of this timer
+        /// changes the total duration of this timer. A cyclic timer without a positive duration never completes a cycle.
         pub fn change_cycle(&mut self, new_cycle: f32) {
             self.total_duration = new_cycle;
         }
@@ -147,15 +276,21 @@ mod timer {
         }
     }
 
+    /// Name, topic and callback of a timer to notify
+    pub(crate) type TimerNotification = (String, Option<String>, Option<fn(&mut GameData)>);
+
     /// Timers is a convenience resource provided by `Scion`
     /// in order to help users to create timers in their systems/layers
-    #[derive(Default)]
+    #[derive(Default, Serialize, Deserialize)]
     pub struct Timers {
-        timers: HashMap<String, Timer>,
+        /// Ordered by name, so that the notifications of a frame are always dispatched in the same order
+        timers: BTreeMap<String, Timer>,
+        #[serde(default)]
+        paused_groups: HashSet<String>,
     }
 
     impl Timers {
-        /// Create and adds a timer to the list of known timers
+        /// Create and adds a timer to the list of known timers. The duration must be positive.
         pub fn add_timer(
             &mut self,
             name: &str,
@@ -165,6 +300,9 @@ mod timer {
             if self.timers.contains_key(name) {
                 return Err(Error::TimerAlreadyExists);
             }
+            if !(duration_in_second > 0. && duration_in_second.is_finite()) {
+                return Err(Error::InvalidDuration);
+            }
             self.timers.insert(name.to_string(), Timer::new(duration_in_second, timer_type));
             Ok(self.timers.get_mut(name).expect("Missing the timer we just inserted..."))
         }
@@ -189,18 +327,69 @@ mod timer {
             self.timers.get_mut(name).ok_or(Error::TimerDoesNotExist)
         }
 
-        pub(crate) fn add_delta_duration(&mut self, delta_duration: Duration) {
+        /// Pauses all the timers of `group`, including the ones added to it later
+        pub fn pause_group(&mut self, group: &str) {
+            self.paused_groups.insert(group.to_string());
+        }
+
+        pub fn resume_group(&mut self, group: &str) {
+            self.paused_groups.remove(group);
+        }
+
+        /// Returns whether or not the timers of `group` are paused
+        pub fn is_group_paused(&self, group: &str) -> bool {
+            self.paused_groups.contains(group)
+        }
+
+        /// Adds the duration to the running timers, and returns the notifications to dispatch in the order of the timer
+        /// names : for each notification, the name, topic and callback of its timer
+        pub(crate) fn add_delta_duration(&mut self, delta_duration: Duration) -> Vec<TimerNotification> {
             let delta = delta_duration.as_secs_f32();
-            self.timers.values_mut().for_each(|timer| {
+            let paused_groups = &self.paused_groups;
+            let mut notifications = Vec::new();
+            for (name, timer) in self.timers.iter_mut() {
+                if timer.group.as_ref().is_some_and(|group| paused_groups.contains(group)) {
+                    timer.dirty = false;
+                    timer.notifications = 0;
+                    continue;
+                }
                 timer.add_delta_duration(delta);
-            })
+                if timer.topic.is_none() && timer.callback.is_none() {
+                    continue;
+                }
+                for _ in 0..timer.notifications {
+                    notifications.push((name.to_string(), timer.topic.clone(), timer.callback));
+                }
+            }
+            notifications
+        }
+    }
+
+    /// Updates the timers with the duration of the frame, then publishes their notifications and executes their callbacks
+    pub(crate) fn update_timers(data: &mut GameData, delta_duration: Duration) {
+        let notifications = data.timers().add_delta_duration(delta_duration);
+        for (name, topic, callback) in notifications {
+            if let Some(topic) = topic {
+                if let Err(e) = data.events().publish(&topic, &name) {
+                    error!("Error while publishing the end of the timer `{}` in the topic `{}`: {:?}", name, topic, e);
+                }
+            }
+            if let Some(callback) = callback {
+                callback(data);
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::core::resources::time::{Timers, TimerType};
+    use std::time::Duration;
+
+    use crate::core::resources::events::topic::TopicConfiguration;
+    use crate::core::resources::events::{Events, PollConfiguration};
+    use crate::core::resources::time::{update_timers, Error, Timers, TimerType};
+    use crate::core::state::GameState;
+    use crate::core::world::GameData;
 
     #[test]
     fn add_timer_test() {
@@ -228,4 +417,98 @@ mod tests {
         assert_eq!(0.5, timer.elapsed());
         assert!(!timer.ended());
     }
+
+    #[test]
+    fn invalid_duration_test() {
+        let mut timers = Timers::default();
+        assert!(matches!(timers.add_timer("zero", TimerType::Cyclic, 0.), Err(Error::InvalidDuration)));
+        assert!(matches!(timers.add_timer("negative", TimerType::Manual, -1.), Err(Error::InvalidDuration)));
+        assert!(matches!(timers.add_timer("nan", TimerType::Cyclic, f32::NAN), Err(Error::InvalidDuration)));
+        assert!(!timers.exists("zero"));
+
+        let timer = timers.add_timer("cyclic", TimerType::Cyclic, 1.).unwrap();
+        timer.change_cycle(0.);
+        assert!(!timer.add_delta_duration(1.));
+        assert_eq!(0, timer.cycle());
+    }
+
+    #[test]
+    fn pause_and_progress_test() {
+        let mut timers = Timers::default();
+        let timer = timers.add_timer("manual", TimerType::Manual, 2.0).unwrap();
+        timer.in_group("gameplay");
+        let _r = timers.add_timer("cyclic", TimerType::Cyclic, 1.0);
+
+        timers.add_delta_duration(Duration::from_millis(500));
+        timers.get_timer("cyclic").unwrap().pause();
+        timers.pause_group("gameplay");
+        timers.add_delta_duration(Duration::from_millis(500));
+        assert!(timers.is_group_paused("gameplay"));
+        assert_eq!(0.5, timers.get_timer("cyclic").unwrap().elapsed());
+
+        let timer = timers.get_timer("manual").unwrap();
+        assert!(!timer.is_paused());
+        assert_eq!(1.5, timer.remaining());
+        assert_eq!(0.25, timer.progress());
+
+        timers.resume_group("gameplay");
+        timers.add_delta_duration(Duration::from_millis(1500));
+        let timer = timers.get_timer("manual").unwrap();
+        assert!(timer.ended());
+        assert_eq!(0., timer.remaining());
+        assert_eq!(1., timer.progress());
+    }
+
+    #[test]
+    fn timer_notifications_test() {
+        let mut data = GameData::default();
+        let mut events = Events::default();
+        events.create_topic("timers", TopicConfiguration::default()).unwrap();
+        let subscriber = events.subscribe("timers", PollConfiguration::with_max_messages(10)).unwrap();
+        data.insert_resource(events);
+        data.insert_resource(Timers::default());
+        data.insert_resource(GameState::default());
+
+        data.timers().add_timer("wave", TimerType::Cyclic, 1.0).unwrap().with_topic("timers");
+        data.timers().add_timer("bomb", TimerType::Manual, 1.5).unwrap().with_callback(|data| {
+            data.game_state_mut().set_bool("exploded", true);
+        });
+
+        update_timers(&mut data, Duration::from_millis(1200));
+        assert!(!data.game_state().get_bool("exploded"));
+        update_timers(&mut data, Duration::from_millis(2000));
+        assert!(data.game_state().get_bool("exploded"));
+
+        let published: Vec<String> = Vec::from(data.events().poll::<String>(&subscriber).unwrap());
+        assert_eq!(vec!["wave", "wave", "wave"], published);
+    }
+
+    #[test]
+    fn notifications_order_test() {
+        let mut data = GameData::default();
+        data.insert_resource(Events::default());
+        data.insert_resource(Timers::default());
+        data.insert_resource(GameState::default());
+
+        fn log(data: &mut GameData, value: &str) {
+            let log = data.game_state().get_text("log").unwrap_or_default();
+            data.game_state_mut().set_text("log", &format!("{}{}", log, value));
+        }
+        for name in ["c", "a", "d", "b"] {
+            let _r = data.timers().add_timer(name, TimerType::Manual, 1.0);
+        }
+        data.timers().get_timer("c").unwrap().with_callback(|data| log(data, "c"));
+        data.timers().get_timer("a").unwrap().with_callback(|data| log(data, "a"));
+        data.timers().get_timer("d").unwrap().in_group("paused").with_callback(|data| log(data, "d"));
+        data.timers().get_timer("b").unwrap().with_callback(|data| log(data, "b"));
+
+        update_timers(&mut data, Duration::from_millis(1500));
+        assert_eq!(Some("abcd".to_string()), data.game_state().get_text("log"));
+
+        data.timers().get_timer("d").unwrap().reset();
+        update_timers(&mut data, Duration::from_millis(1500));
+        data.timers().pause_group("paused");
+        update_timers(&mut data, Duration::from_millis(1500));
+        assert_eq!(Some("abcdd".to_string()), data.game_state().get_text("log"));
+    }
 }
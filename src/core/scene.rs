@@ -1,19 +1,48 @@
 //! Everything that is linked to the running of scenes.
 
-use crate::core::world::GameData;
+use std::time::Duration;
+
+use hecs::Entity;
+
+use crate::core::components::maths::camera::Camera;
+use crate::core::resources::profiler::{profile, ProfileCategory};
+use crate::core::resources::time::Time;
+use crate::core::scheduler::{Scheduler, Stage, SystemConfig, SystemConfigBuilder};
+use crate::core::scene_transition::{RunningTransition, Transition, TransitionStep};
+use crate::core::state::GameState;
+use crate::core::system_param::SystemParamFunction;
+use crate::core::world::{GameData, World};
+use crate::graphics::components::HiddenByScene;
 
 /// Trait to implement in order to define a `Scene`.
 pub trait Scene {
+    /// Will be called once right before `on_start`, to register the systems that are executed only while this scene is updated.
+    /// They are executed in their stage, after the systems of the same stage added to the `ScionBuilder`, and removed
+    /// when the scene is stopped.
+    fn register_systems(&self, _systems: &mut SceneSystems) {}
     /// Will be called once before the new game loop iteration. Useful to initialize resources and add everything you need in the world.
     fn on_start(&mut self, _data: &mut GameData) {}
     /// Will be called each game loop, before the systems execution
     fn on_update(&mut self, _data: &mut GameData) {}
-    /// Will be called each game loop, 60 times per second
+    /// Will be called at the fixed update rate (60 times per second by default), possibly several times per game loop to catch up
     fn on_fixed_update(&mut self, _data: &mut GameData) {}
     /// Will be called each game loop, after the systems execution
     fn late_update(&mut self, _data: &mut GameData) {}
-    /// Will be called for deleted scene at the end of the frame where it was deleted
+    /// Will be called for deleted scene at the end of the frame where it was deleted, or when the application quits
     fn on_stop(&mut self, _data: &mut GameData) {}
+    /// Will be called when another scene is pushed on top of this one
+    fn on_pause(&mut self, _data: &mut GameData) {}
+    /// Will be called when the scene on top of this one is popped, making this one the top scene again
+    fn on_resume(&mut self, _data: &mut GameData) {}
+    /// Will be called when the user asks to close the window. Returning `false` vetoes the closing,
+    /// for example to ask the player to save first. [`GameData::quit`] can then be used to really quit.
+    fn on_close_requested(&mut self, _data: &mut GameData) -> bool {
+        true
+    }
+    /// Name of the scene used by the [`crate::core::resources::profiler::Profiler`], its type name by default
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 pub(crate) enum SceneAction {
@@ -24,78 +53,344 @@ pub(crate) enum SceneAction {
     LateUpdate,
 }
 
-/// `SceneMachine` is the Resource used to control the game scene.
+/// `SceneSystems` holds the systems registered by a scene in [`Scene::register_systems`].
+#[derive(Default)]
+pub struct SceneSystems {
+    scheduler: Scheduler,
+}
+
+impl SceneSystems {
+    /// Adds a system executed at each update of the scene
+    pub fn add_system<F: FnMut(&mut GameData) + Send + 'static>(&mut self, system: F) {
+        self.scheduler.add_system(system);
+    }
+
+    /// Adds a system that is not executed when `pause_condition` returns true
+    pub fn add_pausable_system<F: FnMut(&mut GameData) + Send + 'static>(&mut self, system: F, pause_condition: fn(&GameState) -> bool) {
+        self.scheduler.add_pausable_system(system, pause_condition);
+    }
+
+    /// Adds a typed system, see [`crate::core::system_param`]. Panics if its parameters borrow the same data in conflicting ways.
+    pub fn add_typed_system<F: SystemParamFunction<P>, P: 'static>(&mut self, system: F) {
+        match SystemConfigBuilder::typed(system) {
+            Ok(builder) => self.scheduler.add_system_config(builder.get()),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Adds a system with its stage, label and ordering constraints. The constraints only apply between the systems of the scene.
+    pub fn add_system_config(&mut self, config: SystemConfig) {
+        self.scheduler.add_system_config(config);
+    }
+}
+
+/// `OverlayConfig` tells how a scene pushed on top of others interacts with the scenes below it.
+#[derive(Debug, Copy, Clone)]
+pub struct OverlayConfig {
+    /// Whether the scenes below keep receiving `on_update`, `on_fixed_update` and `late_update`
+    pub update_below: bool,
+    /// Whether the entities existing when the scene is pushed keep being rendered
+    pub render_below: bool,
+}
+
+impl Default for OverlayConfig {
+    fn default() -> Self {
+        Self { update_below: false, render_below: true }
+    }
+}
+
+struct SceneStackEntry {
+    scene: Box<dyn Scene + Send>,
+    started: bool,
+    systems: SceneSystems,
+    overlay: OverlayConfig,
+    /// Entities hidden when this scene was pushed, to restore when it's popped
+    hidden_below: Vec<Entity>,
+}
+
+impl SceneStackEntry {
+    fn new(scene: Box<dyn Scene + Send>, overlay: OverlayConfig) -> Self {
+        Self { scene, started: false, systems: Default::default(), overlay, hidden_below: vec![] }
+    }
+
+    fn start(&mut self, data: &mut GameData) {
+        self.scene.register_systems(&mut self.systems);
+        self.run_hook("on_start", data, |scene, data| scene.on_start(data));
+        self.started = true;
+    }
+
+    /// Executes a hook of the scene, measured by the profiler
+    fn run_hook(&mut self, hook: &str, data: &mut GameData, run: impl FnOnce(&mut (dyn Scene + Send), &mut GameData)) {
+        let name = self.scene.name();
+        let scene = self.scene.as_mut();
+        profile(data, ProfileCategory::SceneHook, || format!("{}::{}", name, hook), |data| run(scene, data));
+    }
+}
+
+/// `SceneMachine` holds the stack of running scenes. Only the top scene is guaranteed to be updated.
 #[derive(Default)]
 pub(crate) struct SceneMachine {
-    pub(crate) current_scene: Option<Box<dyn Scene + Send>>,
-    pub(crate) current_scene_started: bool
+    stack: Vec<SceneStackEntry>,
+    transition: Option<RunningTransition>,
+    /// Scene waiting for the running transition to switch to it
+    next_scene: Option<Box<dyn Scene + Send>>,
+    /// Scenes no longer updated but still rendered until the end of the running transition
+    previous_stack: Vec<SceneStackEntry>,
 }
 
 impl SceneMachine {
-    pub(crate) fn apply_scene_action(&mut self, action: SceneAction, data: &mut GameData) {
-        if let Some(scene) = self.current_scene.as_mut() {
-            match action {
-                SceneAction::Update => {
-                    if !self.current_scene_started{
-                        scene.on_start(data);
-                        self.current_scene_started = true;
-                    }
-                    scene.on_update(data);
-                },
-                SceneAction::Start => {
-                    scene.on_start(data);
-                    self.current_scene_started = true;
-                },
-                SceneAction::EndFrame => {},
-                SceneAction::FixedUpdate => scene.on_fixed_update(data),
-                SceneAction::LateUpdate => scene.late_update(data),
-            };
+    pub(crate) fn new(scene: Option<Box<dyn Scene + Send>>) -> Self {
+        Self {
+            stack: scene.into_iter().map(|scene| SceneStackEntry::new(scene, OverlayConfig::default())).collect(),
+            ..Default::default()
         }
+    }
 
+    pub(crate) fn apply_scene_action(&mut self, action: SceneAction, data: &mut GameData) {
+        let first_updated = self.first_updated_index();
         match action {
+            SceneAction::Update => {
+                for entry in self.stack[first_updated..].iter_mut() {
+                    if !entry.started {
+                        entry.start(data);
+                    }
+                    entry.run_hook("on_update", data, |scene, data| scene.on_update(data));
+                }
+            }
+            SceneAction::Start => {
+                if let Some(entry) = self.stack.last_mut() {
+                    entry.start(data);
+                }
+            }
+            SceneAction::FixedUpdate => {
+                self.stack[first_updated..]
+                    .iter_mut()
+                    .filter(|e| e.started)
+                    .for_each(|e| e.run_hook("on_fixed_update", data, |scene, data| scene.on_fixed_update(data)))
+            }
+            SceneAction::LateUpdate => {
+                self.stack[first_updated..]
+                    .iter_mut()
+                    .filter(|e| e.started)
+                    .for_each(|e| e.run_hook("late_update", data, |scene, data| scene.late_update(data)))
+            }
             SceneAction::EndFrame => {
-                let action = data.scene_controller().action();
-                match action {
-                    Some(SceneTrans::Switch(new_scene)) => {
-                        if let Some(mut scene) = self.current_scene.take() {
-                            scene.on_stop(data);
+                let delta = data.get_resource::<Time>().map_or(Duration::ZERO, |time| time.delta_duration());
+                self.advance_transition(delta, data);
+                let actions = data.scene_controller().actions();
+                for action in actions {
+                    match action {
+                        SceneTrans::Switch(new_scene) => {
+                            self.finish_transition(data);
+                            self.stop_stack(data);
+                            self.stack.push(SceneStackEntry::new(new_scene, OverlayConfig::default()));
                         }
-                        self.current_scene = Some(new_scene);
-                        self.current_scene_started = false;
+                        SceneTrans::SwitchWithTransition(new_scene, transition) => {
+                            self.finish_transition(data);
+                            self.next_scene = Some(new_scene);
+                            self.transition = Some(RunningTransition::new(transition));
+                            self.advance_transition(Duration::ZERO, data);
+                        }
+                        SceneTrans::Push(new_scene, overlay) => self.push(new_scene, overlay, data),
+                        SceneTrans::Pop => self.pop(data),
                     }
-                    _ => {}
                 }
             }
-            _ => {}
         }
     }
+
+    /// Executes the systems of `stage` registered by the scenes currently updated, from the bottom of the stack to the top
+    pub(crate) fn execute_systems(&mut self, stage: Stage, data: &mut GameData) {
+        let first_updated = self.first_updated_index();
+        self.stack[first_updated..]
+            .iter_mut()
+            .filter(|e| e.started)
+            .for_each(|e| e.systems.scheduler.execute_stage(stage, data))
+    }
+
+    /// Asks the current scene if the application can be closed
+    pub(crate) fn close_requested(&mut self, data: &mut GameData) -> bool {
+        self.stack.last_mut().is_none_or(|entry| entry.scene.on_close_requested(data))
+    }
+
+    /// Stops all the scenes, from top to bottom, and cancels the running transition. Used when the application quits.
+    pub(crate) fn stop(&mut self, data: &mut GameData) {
+        if let Some(transition) = self.transition.take() {
+            transition.clean(data);
+        }
+        self.next_scene = None;
+        self.stop_stack(data);
+        Self::stop_entries(&mut self.previous_stack, data);
+    }
+
+    fn stop_stack(&mut self, data: &mut GameData) {
+        Self::stop_entries(&mut self.stack, data);
+    }
+
+    fn stop_entries(entries: &mut Vec<SceneStackEntry>, data: &mut GameData) {
+        while let Some(entry) = entries.pop() {
+            Self::stop_entry(entry, data);
+        }
+    }
+
+    fn advance_transition(&mut self, delta: Duration, data: &mut GameData) {
+        if let Some(mut transition) = self.transition.take() {
+            match transition.advance(delta) {
+                TransitionStep::Running => {}
+                TransitionStep::Switch => self.switch_during_transition(&mut transition, data),
+                TransitionStep::Finished => {
+                    Self::stop_entries(&mut self.previous_stack, data);
+                    transition.clean(data);
+                    return;
+                }
+            }
+            transition.animate(data);
+            self.transition = Some(transition);
+        }
+    }
+
+    /// Immediately ends the running transition, switching scenes if it was not done yet
+    fn finish_transition(&mut self, data: &mut GameData) {
+        if let Some(mut transition) = self.transition.take() {
+            if transition.advance(Duration::MAX) == TransitionStep::Switch {
+                self.switch_during_transition(&mut transition, data);
+            }
+            Self::stop_entries(&mut self.previous_stack, data);
+            transition.clean(data);
+        }
+    }
+
+    fn switch_during_transition(&mut self, transition: &mut RunningTransition, data: &mut GameData) {
+        if let Some(new_scene) = self.next_scene.take() {
+            if transition.keeps_previous_scenes() {
+                transition.record_previous_entities(data);
+                self.previous_stack = std::mem::take(&mut self.stack);
+            } else {
+                self.stop_stack(data);
+            }
+            self.stack.push(SceneStackEntry::new(new_scene, OverlayConfig::default()));
+        }
+    }
+
+    /// Index of the lowest scene of the stack receiving the updates
+    fn first_updated_index(&self) -> usize {
+        let mut index = self.stack.len().saturating_sub(1);
+        while index > 0 && self.stack[index].overlay.update_below {
+            index -= 1;
+        }
+        index
+    }
+
+    fn push(&mut self, scene: Box<dyn Scene + Send>, overlay: OverlayConfig, data: &mut GameData) {
+        if let Some(top) = self.stack.last_mut() {
+            if top.started {
+                top.scene.on_pause(data);
+            }
+        }
+        let mut entry = SceneStackEntry::new(scene, overlay);
+        if !overlay.render_below {
+            entry.hidden_below = data
+                .query::<()>()
+                .without::<&HiddenByScene>()
+                .without::<&Camera>()
+                .iter()
+                .map(|(e, _)| e)
+                .collect();
+            entry.hidden_below.iter().for_each(|e| {
+                let _r = data.add_components(*e, (HiddenByScene,));
+            });
+        }
+        self.stack.push(entry);
+    }
+
+    fn pop(&mut self, data: &mut GameData) {
+        if let Some(entry) = self.stack.pop() {
+            Self::stop_entry(entry, data);
+            if let Some(top) = self.stack.last_mut() {
+                if top.started {
+                    top.scene.on_resume(data);
+                }
+            }
+        }
+    }
+
+    fn stop_entry(mut entry: SceneStackEntry, data: &mut GameData) {
+        if entry.started {
+            entry.scene.on_stop(data);
+        }
+        entry.hidden_below.drain(..).for_each(|e| {
+            let _r = data.remove_component::<HiddenByScene>(e);
+        });
+    }
 }
 
 pub(crate) enum SceneTrans {
-    Switch(Box<dyn Scene + Send>)
+    Switch(Box<dyn Scene + Send>),
+    SwitchWithTransition(Box<dyn Scene + Send>, Transition),
+    Push(Box<dyn Scene + Send>, OverlayConfig),
+    Pop,
 }
 
 /// `SceneController` is the Resource used to control the game scenes.
+/// All the requested actions are executed, in the order of the calls, at the end of the frame.
 #[derive(Default)]
 pub struct SceneController {
-    /// scene action that has to be executed at the end of the frame
-    pub(crate) action: Option<SceneTrans>,
+    /// scene actions that have to be executed at the end of the frame
+    pub(crate) actions: Vec<SceneTrans>,
 }
 
 impl SceneController {
-    /// Replace the current scene with the name `` with the scene created from type `T`. (Useful for level switching).
-    /// Note that the scene's stop will happen at the end of the frame.
+    /// Replace all the running scenes with the scene created from type `T`. (Useful for level switching).
+    /// Note that the scenes' stop will happen at the end of the frame.
     pub fn switch<T: Scene + Default + Send + 'static>(&mut self) {
-        self.action = Some(SceneTrans::Switch(Box::<T>::default()));
+        self.switch_to(T::default());
+    }
+
+    /// Replace all the running scenes with the given `scene`. Useful to pass parameters to the scene.
+    pub fn switch_to<T: Scene + Send + 'static>(&mut self, scene: T) {
+        self.actions.push(SceneTrans::Switch(Box::new(scene)));
+    }
+
+    /// Replace all the running scenes with the scene created from type `T`, animating the screen with `transition`.
+    /// The previous scenes are stopped and the new one is started when the transition switches scenes, see [`Transition`].
+    pub fn switch_with_transition<T: Scene + Default + Send + 'static>(&mut self, transition: Transition) {
+        self.switch_to_with_transition(T::default(), transition);
     }
 
-    pub(crate) fn action(&mut self) -> Option<SceneTrans> {
-        self.action.take()
+    /// Replace all the running scenes with the given `scene`, animating the screen with `transition`.
+    pub fn switch_to_with_transition<T: Scene + Send + 'static>(&mut self, scene: T, transition: Transition) {
+        self.actions.push(SceneTrans::SwitchWithTransition(Box::new(scene), transition));
+    }
+
+    /// Push the scene created from type `T` on top of the current one, like a pause menu or an inventory.
+    /// The current scene is paused, and `overlay` tells if it is still updated and rendered.
+    pub fn push<T: Scene + Default + Send + 'static>(&mut self, overlay: OverlayConfig) {
+        self.push_scene(T::default(), overlay);
+    }
+
+    /// Push the given `scene` on top of the current one. See [`SceneController::push`]
+    pub fn push_scene<T: Scene + Send + 'static>(&mut self, scene: T, overlay: OverlayConfig) {
+        self.actions.push(SceneTrans::Push(Box::new(scene), overlay));
+    }
+
+    /// Pop the top scene, stopping it and resuming the one below.
+    pub fn pop(&mut self) {
+        self.actions.push(SceneTrans::Pop);
+    }
+
+    pub(crate) fn actions(&mut self) -> Vec<SceneTrans> {
+        std::mem::take(&mut self.actions)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::config::scion_config::ScionConfigBuilder;
+    use crate::core::state::GameState;
+    use crate::ScionBuilder;
+    use crate::graphics::components::color::Color;
+    use crate::graphics::components::TransitionOpacity;
+
     use super::*;
 
     #[derive(Default)]
@@ -107,22 +402,227 @@ mod tests {
     #[derive(Default)]
     struct C;
 
-    #[derive(Default)]
-    struct D;
+    /// Scene logging its hooks calls in the game state text, under its name
+    struct Logged(&'static str);
 
     impl Scene for A {}
 
     impl Scene for B {}
 
-    #[test]
-    fn switch_scene_should_replace_at_same_index() {
+    impl Scene for C {
+        fn on_close_requested(&mut self, data: &mut GameData) -> bool {
+            data.game_state_mut().set_bool("close_vetoed", true);
+            false
+        }
+
+        fn on_stop(&mut self, data: &mut GameData) {
+            data.game_state_mut().set_bool("stopped", true);
+        }
+    }
+
+    impl Logged {
+        fn log(&self, data: &mut GameData, hook: &str) {
+            let log = data.game_state().get_text(self.0).unwrap_or_default();
+            data.game_state_mut().set_text(self.0, &format!("{}{};", log, hook));
+        }
+    }
+
+    impl Scene for Logged {
+        fn on_start(&mut self, data: &mut GameData) {
+            self.log(data, "start");
+        }
+
+        fn on_update(&mut self, data: &mut GameData) {
+            self.log(data, "update");
+        }
+
+        fn on_stop(&mut self, data: &mut GameData) {
+            self.log(data, "stop");
+        }
+
+        fn on_pause(&mut self, data: &mut GameData) {
+            self.log(data, "pause");
+        }
+
+        fn on_resume(&mut self, data: &mut GameData) {
+            self.log(data, "resume");
+        }
+    }
+
+    fn world() -> GameData {
         let mut world = GameData::default();
         world.insert_resource(SceneController::default());
+        world.insert_resource(GameState::default());
+        world
+    }
+
+    fn log_of(world: &GameData, name: &str) -> String {
+        world.game_state().get_text(name).unwrap_or_default()
+    }
+
+    #[test]
+    fn switch_scene_should_replace_at_same_index() {
+        let mut world = world();
 
-        let scene = Box::new(A);
-        let mut machine = SceneMachine { current_scene: Some(scene), current_scene_started: false };
+        let mut machine = SceneMachine::new(Some(Box::new(A)));
 
         world.scene_controller().switch::<B>();
         machine.apply_scene_action(SceneAction::EndFrame, &mut world);
+        assert_eq!(1, machine.stack.len());
+    }
+
+    #[test]
+    fn close_requested_can_be_vetoed_and_stop_calls_on_stop() {
+        let mut world = world();
+
+        let mut machine = SceneMachine::new(Some(Box::new(A)));
+        assert!(machine.close_requested(&mut world));
+
+        let mut machine = SceneMachine::new(Some(Box::new(C)));
+        machine.apply_scene_action(SceneAction::Start, &mut world);
+        assert!(!machine.close_requested(&mut world));
+        assert!(world.game_state().get_bool("close_vetoed"));
+
+        machine.stop(&mut world);
+        assert!(world.game_state().get_bool("stopped"));
+        assert!(machine.stack.is_empty());
+    }
+
+    #[test]
+    fn push_and_pop_overlay_test() {
+        let mut world = world();
+        let mut machine = SceneMachine::new(Some(Box::new(Logged("game"))));
+        machine.apply_scene_action(SceneAction::Start, &mut world);
+        let below = world.push((1,));
+
+        world.scene_controller().push_scene(Logged("menu"), OverlayConfig { update_below: false, render_below: false });
+        machine.apply_scene_action(SceneAction::EndFrame, &mut world);
+        assert!(world.entry::<&HiddenByScene>(below).unwrap().get().is_some());
+
+        machine.apply_scene_action(SceneAction::Update, &mut world);
+        assert_eq!("start;pause;", log_of(&world, "game"));
+        assert_eq!("start;update;", log_of(&world, "menu"));
+
+        world.scene_controller().pop();
+        machine.apply_scene_action(SceneAction::EndFrame, &mut world);
+        machine.apply_scene_action(SceneAction::Update, &mut world);
+        assert_eq!("start;pause;resume;update;", log_of(&world, "game"));
+        assert_eq!("start;update;stop;", log_of(&world, "menu"));
+        assert!(world.entry::<&HiddenByScene>(below).unwrap().get().is_none());
+    }
+
+    #[test]
+    fn overlay_can_keep_updating_below_test() {
+        let mut world = world();
+        let mut machine = SceneMachine::new(Some(Box::new(Logged("game"))));
+        machine.apply_scene_action(SceneAction::Start, &mut world);
+
+        world.scene_controller().push_scene(Logged("hud"), OverlayConfig { update_below: true, render_below: true });
+        machine.apply_scene_action(SceneAction::EndFrame, &mut world);
+        machine.apply_scene_action(SceneAction::Update, &mut world);
+
+        assert_eq!("start;pause;update;", log_of(&world, "game"));
+        assert_eq!("start;update;", log_of(&world, "hud"));
+    }
+
+    #[derive(Default)]
+    struct WithSystem;
+
+    impl Scene for WithSystem {
+        fn register_systems(&self, systems: &mut SceneSystems) {
+            systems.add_system(|data| {
+                let count = data.game_state().get_text("system").unwrap_or_default();
+                data.game_state_mut().set_text("system", &format!("{}x", count));
+            });
+        }
+    }
+
+    #[test]
+    fn scene_systems_run_only_while_scene_is_updated_test() {
+        let mut world = world();
+        let mut machine = SceneMachine::new(Some(Box::new(WithSystem)));
+        machine.apply_scene_action(SceneAction::Start, &mut world);
+        machine.execute_systems(Stage::Update, &mut world);
+        assert_eq!("x", log_of(&world, "system"));
+
+        world.scene_controller().push::<A>(OverlayConfig::default());
+        machine.apply_scene_action(SceneAction::EndFrame, &mut world);
+        machine.apply_scene_action(SceneAction::Update, &mut world);
+        machine.execute_systems(Stage::Update, &mut world);
+        assert_eq!("x", log_of(&world, "system"));
+
+        world.scene_controller().switch::<A>();
+        machine.apply_scene_action(SceneAction::EndFrame, &mut world);
+        machine.apply_scene_action(SceneAction::Update, &mut world);
+        machine.execute_systems(Stage::Update, &mut world);
+        assert_eq!("x", log_of(&world, "system"));
+    }
+
+    struct Spawned;
+
+    fn log_spawned(data: &mut GameData, prefix: &str) {
+        let count = data.query::<&Spawned>().iter().count();
+        let log = log_of(data, "staged");
+        data.game_state_mut().set_text("staged", &format!("{}{}{}", log, prefix, count));
+    }
+
+    #[derive(Default)]
+    struct WithStagedSystems;
+
+    impl Scene for WithStagedSystems {
+        fn register_systems(&self, systems: &mut SceneSystems) {
+            systems.add_system_config(
+                SystemConfigBuilder::new(|data: &mut GameData| data.commands().spawn((Spawned,)))
+                    .with_stage(Stage::PreUpdate)
+                    .get(),
+            );
+            systems.add_system_config(
+                SystemConfigBuilder::new(|data: &mut GameData| log_spawned(data, "P")).with_stage(Stage::PostUpdate).get(),
+            );
+        }
+    }
+
+    #[test]
+    fn scene_systems_run_in_their_stage_test() {
+        let mut app = ScionBuilder::new(ScionConfigBuilder::new().without_window().get())
+            .with_scene::<WithStagedSystems>()
+            .with_system(|data: &mut GameData| log_spawned(data, "U"))
+            .build_test_app();
+
+        app.step();
+        assert_eq!("U1P1", log_of(app.game_data(), "staged"));
+    }
+
+    #[test]
+    fn fade_transition_switches_when_screen_is_covered_test() {
+        let mut world = world();
+        world.insert_resource(Time::default());
+        world.insert_resource(crate::core::resources::window::Window::new((100, 100), 1.));
+        let mut machine = SceneMachine::new(Some(Box::new(Logged("menu"))));
+        machine.apply_scene_action(SceneAction::Start, &mut world);
+
+        let fade = Transition::FadeToColor { color: Color::new_rgb(0, 0, 0), duration: Duration::from_millis(100) };
+        world.scene_controller().switch_to_with_transition(Logged("level"), fade);
+        let frame = |machine: &mut SceneMachine, world: &mut GameData| {
+            world.get_resource_mut::<Time>().unwrap().frame_with_duration(Duration::from_millis(20));
+            machine.apply_scene_action(SceneAction::Update, world);
+            machine.apply_scene_action(SceneAction::EndFrame, world);
+        };
+
+        // The transition starts at the end of the frame where it was requested
+        frame(&mut machine, &mut world);
+        frame(&mut machine, &mut world);
+        frame(&mut machine, &mut world);
+        assert_eq!("start;update;update;update;", log_of(&world, "menu"));
+        assert_eq!(1, world.query::<&TransitionOpacity>().iter().count());
+
+        frame(&mut machine, &mut world);
+        assert_eq!("start;update;update;update;update;stop;", log_of(&world, "menu"));
+        assert_eq!("", log_of(&world, "level"));
+
+        frame(&mut machine, &mut world);
+        assert_eq!("start;update;", log_of(&world, "level"));
+        frame(&mut machine, &mut world);
+        assert_eq!(0, world.query::<&TransitionOpacity>().iter().count());
     }
 }
@@ -0,0 +1,253 @@
+//! Animated transitions used when switching from a scene to another.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use hecs::Entity;
+
+use crate::core::components::maths::camera::Camera;
+use crate::core::components::maths::transform::Transform;
+use crate::core::world::{GameData, World};
+use crate::graphics::components::color::Color;
+use crate::graphics::components::material::Material;
+use crate::graphics::components::TransitionOpacity;
+use crate::graphics::components::ui::ui_image::UiImage;
+use crate::graphics::components::ui::UiComponent;
+
+/// Layer used by the transition overlay, above the layers used by the games
+const OVERLAY_LAYER: usize = 99;
+
+/// `Transition` describes how the screen animates when switching scenes with
+/// [`crate::core::scene::SceneController::switch_with_transition`].
+#[derive(Debug, Clone)]
+pub enum Transition {
+    /// The screen fades to `color` during the first half of `duration`, the scenes are switched
+    /// while the screen is fully covered, then the new scene fades in during the second half.
+    FadeToColor { color: Color, duration: Duration },
+    /// The new scene is started immediately and fades in while the previous one, which is no longer updated,
+    /// fades out. The previous scene is stopped at the end of `duration`.
+    Crossfade { duration: Duration },
+    /// A `color` curtain slides over the screen in `direction` during the first half of `duration`, the scenes are
+    /// switched while the screen is fully covered, then the curtain keeps sliding away during the second half.
+    Wipe { color: Color, duration: Duration, direction: WipeDirection },
+}
+
+/// Direction in which a [`Transition::Wipe`] curtain slides
+#[derive(Debug, Copy, Clone)]
+pub enum WipeDirection {
+    LeftToRight,
+    RightToLeft,
+    TopToBottom,
+    BottomToTop,
+}
+
+impl Transition {
+    fn duration(&self) -> Duration {
+        match self {
+            Transition::FadeToColor { duration, .. }
+            | Transition::Crossfade { duration }
+            | Transition::Wipe { duration, .. } => *duration,
+        }
+    }
+}
+
+/// Step reached by a running transition after its time advanced
+#[derive(Debug, PartialEq)]
+pub(crate) enum TransitionStep {
+    Running,
+    /// The scenes have to be switched during this frame
+    Switch,
+    Finished,
+}
+
+/// State of the transition currently running in the `SceneMachine`
+pub(crate) struct RunningTransition {
+    transition: Transition,
+    elapsed: Duration,
+    switched: bool,
+    overlay: Option<Entity>,
+    /// Entities of the previous scene, faded out by a crossfade
+    previous_entities: HashSet<Entity>,
+}
+
+impl RunningTransition {
+    pub(crate) fn new(transition: Transition) -> Self {
+        Self { transition, elapsed: Duration::ZERO, switched: false, overlay: None, previous_entities: HashSet::new() }
+    }
+
+    /// Whether the previous scenes have to be kept until the end of the transition instead of being stopped on switch
+    pub(crate) fn keeps_previous_scenes(&self) -> bool {
+        matches!(self.transition, Transition::Crossfade { .. })
+    }
+
+    /// Advances the transition by `delta` and tells what the `SceneMachine` has to do.
+    /// A crossfade switches immediately, the other transitions switch at half of their duration.
+    pub(crate) fn advance(&mut self, delta: Duration) -> TransitionStep {
+        self.elapsed = self.elapsed.saturating_add(delta);
+        let progress = self.progress();
+        if !self.switched && (self.keeps_previous_scenes() || progress >= 0.5) {
+            self.switched = true;
+            TransitionStep::Switch
+        } else if progress >= 1. {
+            TransitionStep::Finished
+        } else {
+            TransitionStep::Running
+        }
+    }
+
+    /// Must be called right before the new scene is added, so that the crossfade knows which entities to fade out
+    pub(crate) fn record_previous_entities(&mut self, data: &mut GameData) {
+        if self.keeps_previous_scenes() {
+            self.previous_entities =
+                data.query::<()>().without::<&Camera>().iter().map(|(e, _)| e).collect();
+        }
+    }
+
+    /// Updates the overlay or the entities opacity according to the current progress. The components are inserted
+    /// once, when the overlay or an entity is first animated, then modified in place.
+    pub(crate) fn animate(&mut self, data: &mut GameData) {
+        let progress = self.progress();
+        // Part of the screen covered by the overlay, growing to 1 until the switch then decreasing to 0
+        let coverage = if progress < 0.5 { progress * 2. } else { (1. - progress) * 2. };
+        match self.transition.clone() {
+            Transition::FadeToColor { color, .. } => {
+                let overlay = self.overlay(data, &color);
+                if let Ok(opacity) = data.entry_mut::<&mut TransitionOpacity>(overlay) {
+                    opacity.0 = coverage;
+                }
+            }
+            Transition::Wipe { color, direction, .. } => {
+                let overlay = self.overlay(data, &color);
+                let (width, height) = window_size(data);
+                // The curtain comes from outside the screen, then leaves by the opposite side
+                let offset = if progress < 0.5 { coverage - 1. } else { 1. - coverage };
+                let (x, y) = match direction {
+                    WipeDirection::LeftToRight => (offset * width, 0.),
+                    WipeDirection::RightToLeft => (-offset * width, 0.),
+                    WipeDirection::TopToBottom => (0., offset * height),
+                    WipeDirection::BottomToTop => (0., -offset * height),
+                };
+                if let Ok(transform) = data.entry_mut::<&mut Transform>(overlay) {
+                    transform.set_x(x);
+                    transform.set_y(y);
+                }
+            }
+            Transition::Crossfade { .. } => {
+                let opacity = |entity: &Entity| if self.previous_entities.contains(entity) { 1. - progress } else { progress };
+                for (entity, transition_opacity) in
+                    data.query_mut::<&mut TransitionOpacity>().without::<&Camera>()
+                {
+                    transition_opacity.0 = opacity(&entity);
+                }
+                // Entities spawned since the previous frame
+                let entities: Vec<Entity> = data
+                    .query::<()>()
+                    .without::<&Camera>()
+                    .without::<&TransitionOpacity>()
+                    .iter()
+                    .map(|(e, _)| e)
+                    .collect();
+                for entity in entities {
+                    let _r = data.add_components(entity, (TransitionOpacity(opacity(&entity)),));
+                }
+            }
+        }
+    }
+
+    /// Removes everything added by the transition
+    pub(crate) fn clean(self, data: &mut GameData) {
+        if let Some(overlay) = self.overlay {
+            let _r = data.remove(overlay);
+        }
+        let entities: Vec<Entity> = data.query::<&TransitionOpacity>().iter().map(|(e, _)| e).collect();
+        for entity in entities {
+            let _r = data.remove_component::<TransitionOpacity>(entity);
+        }
+    }
+
+    fn progress(&self) -> f32 {
+        let duration = self.transition.duration();
+        if duration.is_zero() {
+            1.
+        } else {
+            (self.elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.)
+        }
+    }
+
+    /// Retrieves the overlay entity, creating it again if a scene removed it
+    fn overlay(&mut self, data: &mut GameData, color: &Color) -> Entity {
+        match self.overlay {
+            Some(overlay) if data.contains(overlay) => overlay,
+            _ => {
+                let (width, height) = window_size(data);
+                let overlay = data.push((
+                    UiImage::new(width, height),
+                    UiComponent,
+                    Transform::from_xyz(0., 0., OVERLAY_LAYER),
+                    Material::Diffuse(color.clone()),
+                    TransitionOpacity(0.),
+                ));
+                self.overlay = Some(overlay);
+                overlay
+            }
+        }
+    }
+}
+
+fn window_size(data: &GameData) -> (f32, f32) {
+    let window = data.window();
+    (window.width() as f32, window.height() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fade_switches_at_half_duration_test() {
+        let mut transition = RunningTransition::new(Transition::FadeToColor {
+            color: Color::new_rgb(0, 0, 0),
+            duration: Duration::from_millis(100),
+        });
+        assert_eq!(TransitionStep::Running, transition.advance(Duration::from_millis(40)));
+        assert_eq!(TransitionStep::Switch, transition.advance(Duration::from_millis(10)));
+        assert_eq!(TransitionStep::Running, transition.advance(Duration::from_millis(40)));
+        assert_eq!(TransitionStep::Finished, transition.advance(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn crossfade_switches_immediately_test() {
+        let mut transition = RunningTransition::new(Transition::Crossfade { duration: Duration::from_millis(100) });
+        assert_eq!(TransitionStep::Switch, transition.advance(Duration::ZERO));
+        assert_eq!(TransitionStep::Running, transition.advance(Duration::from_millis(50)));
+        assert_eq!(TransitionStep::Finished, transition.advance(Duration::from_millis(50)));
+    }
+
+    #[test]
+    fn crossfade_opacity_test() {
+        let mut data = GameData::default();
+        let mut transition = RunningTransition::new(Transition::Crossfade { duration: Duration::from_millis(100) });
+        let previous = data.push((1,));
+        transition.advance(Duration::ZERO);
+        transition.record_previous_entities(&mut data);
+        let next = data.push((2,));
+
+        transition.advance(Duration::from_millis(25));
+        transition.animate(&mut data);
+        assert_eq!(0.75, data.entry::<&TransitionOpacity>(previous).unwrap().get().unwrap().0);
+        assert_eq!(0.25, data.entry::<&TransitionOpacity>(next).unwrap().get().unwrap().0);
+
+        // Opacities are then updated in place, only the entities spawned meanwhile receive one
+        let tick = data.changes().change_tick();
+        data.changes().begin_system();
+        let spawned = data.push((3,));
+        transition.advance(Duration::from_millis(25));
+        transition.animate(&mut data);
+        assert_eq!(vec![spawned], data.changes().added::<TransitionOpacity>(tick));
+        assert_eq!(0.5, data.entry::<&TransitionOpacity>(previous).unwrap().get().unwrap().0);
+        assert_eq!(0.5, data.entry::<&TransitionOpacity>(spawned).unwrap().get().unwrap().0);
+
+        transition.clean(&mut data);
+        assert!(data.query::<&TransitionOpacity>().iter().next().is_none());
+    }
+}
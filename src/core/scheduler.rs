@@ -1,30 +1,771 @@
-use std::collections::LinkedList;
+//! Everything that is linked to the ordering of the systems.
 
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use log::warn;
+
+use crate::core::app_state::{AppState, AppStates};
+use crate::core::resources::commands::apply_commands;
+use crate::core::resources::profiler::{profiling, record, ProfileCategory, FIRST_WORKER_THREAD, MAIN_THREAD};
 use crate::core::state::GameState;
+use crate::core::system_param::{ParallelSystem, SystemAccess, SystemParamError, SystemParamFunction, TypedSystem};
 use crate::core::world::GameData;
 
-#[derive(Default)]
+/// `System` is the trait implemented by everything the scheduler can execute. It is implemented for any
+/// `FnMut(&mut GameData) + Send`, so functions and closures capturing their own state can be used directly.
+/// Implementing it on a struct gives access to `init`.
+pub trait System: Send {
+    /// Will be called once, right before the first `run`
+    fn init(&mut self, _data: &mut GameData) {}
+    /// Will be called each time the system is executed
+    fn run(&mut self, data: &mut GameData);
+}
+
+impl<F: FnMut(&mut GameData) + Send> System for F {
+    fn run(&mut self, data: &mut GameData) {
+        self(data)
+    }
+}
+
+/// Stages in which the systems are executed, in this order, during each frame between the scenes `on_update` and `late_update`.
+/// The internal systems of `Scion` preparing the game logic, like the collisions and the ui inputs, are executed in
+/// `PreUpdate`. The ones reacting to its modifications, like the texts and the debug colliders, are executed in
+/// `PostUpdate` and the ones propagating the hierarchy and transforms before the rendering in `PreRender`, so that they
+/// see the entities spawned by the commands of `PostUpdate`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    PreUpdate,
+    Update,
+    PostUpdate,
+    PreRender,
+}
+
+impl Stage {
+    pub(crate) const ALL: [Stage; 4] = [Stage::PreUpdate, Stage::Update, Stage::PostUpdate, Stage::PreRender];
+}
+
+/// `ExecutionMode` tells how the scheduler executes the systems of a stage
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ExecutionMode {
+    /// Systems are executed one after the other, in a deterministic order
+    Sequential,
+    /// Typed systems whose accesses don't conflict and without ordering constraints between them are executed
+    /// in parallel, each on its own scoped thread. Other systems use the whole `GameData` and are executed alone.
+    Parallel,
+}
+
+/// Errors that can happen while ordering the systems
+#[derive(Debug, PartialEq)]
+pub enum SchedulerError {
+    /// The `before` and `after` constraints of the listed systems can't be satisfied together
+    CycleDetected { stage: Stage, systems: Vec<String> },
+    /// `system` of `stage` must be executed before or after systems labelled `label` of a stage executed respectively
+    /// earlier or later
+    UnsatisfiableConstraint { stage: Stage, system: String, label: String },
+}
+
+impl Display for SchedulerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchedulerError::CycleDetected { stage, systems } => {
+                write!(f, "Cycle detected in the ordering constraints of stage {:?} between systems {:?}", stage, systems)
+            }
+            SchedulerError::UnsatisfiableConstraint { stage, system, label } => write!(
+                f,
+                "System {} of stage {:?} has a constraint on the systems labelled `{}` of another stage that the stages order contradicts",
+                system, stage, label
+            ),
+        }
+    }
+}
+
+/// `SystemConfig` describes a system with its stage, label and ordering constraints.
+/// Please use [`SystemConfigBuilder`] to create one.
+pub struct SystemConfig {
+    system: SystemRunner,
+    /// Type name of the system, used by the profiler when it has no label or shares it with other systems
+    type_name: &'static str,
+    /// Whether other systems of the scheduler have the same label, computed when the scheduler is built
+    shared_label: bool,
+    initialized: bool,
+    exclusive: bool,
+    pause_condition: Option<fn(&GameState) -> bool>,
+    /// The system is only executed when this returns true, see [`SystemConfigBuilder::in_states`]
+    run_condition: Option<Box<dyn Fn(&GameData) -> bool + Send + Sync>>,
+    stage: Stage,
+    label: Option<&'static str>,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+}
+
+/// `SystemConfigBuilder` is a convenience builder to create a `SystemConfig`.
+///
+/// Internal systems are labelled with their function name, without generic parameters, for example
+/// `compute_collisions_system` or `dirty_transform_system`. The instances of a generic internal system, like
+/// `collider_pivot_propagation_system`, share the same label.
+pub struct SystemConfigBuilder {
+    config: SystemConfig,
+}
+
+impl SystemConfigBuilder {
+    /// Creates a configuration for `system`, executed in [`Stage::Update`] without any constraint
+    pub fn new<S: System + 'static>(system: S) -> Self {
+        Self {
+            config: SystemConfig {
+                system: SystemRunner::Exclusive(Box::new(system)),
+                type_name: std::any::type_name::<S>(),
+                shared_label: false,
+                initialized: false,
+                exclusive: false,
+                pause_condition: None,
+                run_condition: None,
+                stage: Stage::Update,
+                label: None,
+                before: vec![],
+                after: vec![],
+            },
+        }
+    }
+
+    /// Creates a configuration for a typed system (see [`crate::core::system_param`]), that can be executed in parallel
+    /// of the other typed systems. Fails if its parameters borrow the same data in conflicting ways.
+    pub fn typed<F: SystemParamFunction<P>, P: 'static>(function: F) -> Result<Self, SystemParamError> {
+        let mut builder = Self::new(|_: &mut GameData| {});
+        builder.config.system = SystemRunner::Parallel(Box::new(TypedSystem::new(function)?));
+        builder.config.type_name = std::any::type_name::<F>();
+        Ok(builder)
+    }
+
+    /// The system is never executed in parallel of other systems
+    pub fn exclusive(mut self) -> Self {
+        self.config.exclusive = true;
+        self
+    }
+
+    /// Stage in which the system is executed
+    pub fn with_stage(mut self, stage: Stage) -> Self {
+        self.config.stage = stage;
+        self
+    }
+
+    /// Label used by other systems to declare constraints against this one. Several systems can share a label, a
+    /// constraint on the label then applies to all of them.
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        self.config.label = Some(label);
+        self
+    }
+
+    /// The system is not executed when `pause_condition` returns true
+    pub fn with_pause_condition(mut self, pause_condition: fn(&GameState) -> bool) -> Self {
+        self.config.pause_condition = Some(pause_condition);
+        self
+    }
+
+    /// The system is only executed when the current application state of type `S` is one of `states`,
+    /// see [`crate::ScionBuilder::with_app_state`]
+    pub fn in_states<S: AppState>(mut self, states: &[S]) -> Self {
+        let states = states.to_vec();
+        self.config.run_condition = Some(Box::new(move |data: &GameData| {
+            data.get_resource::<AppStates<S>>().is_some_and(|app_states| states.contains(&app_states.current()))
+        }));
+        self
+    }
+
+    /// The system is executed before the systems labelled `label`. Only systems of the same stage
+    /// are reordered, a label of a later stage is already executed after, and a label of an earlier stage
+    /// fails the scheduler build with [`SchedulerError::UnsatisfiableConstraint`].
+    pub fn before(mut self, label: &'static str) -> Self {
+        self.config.before.push(label);
+        self
+    }
+
+    /// The system is executed after the systems labelled `label`. Only systems of the same stage
+    /// are reordered, a label of an earlier stage is already executed before, and a label of a later stage
+    /// fails the scheduler build with [`SchedulerError::UnsatisfiableConstraint`].
+    pub fn after(mut self, label: &'static str) -> Self {
+        self.config.after.push(label);
+        self
+    }
+
+    /// Retrieves the configuration built
+    pub fn get(self) -> SystemConfig {
+        self.config
+    }
+}
+
+enum SystemRunner {
+    Exclusive(Box<dyn System>),
+    Parallel(Box<dyn ParallelSystem>),
+}
+
+impl SystemConfig {
+    /// Access of the system if it can be executed in parallel
+    fn parallel_access(&self) -> Option<&SystemAccess> {
+        match &self.system {
+            SystemRunner::Parallel(system) if !self.exclusive => Some(system.access()),
+            _ => None,
+        }
+    }
+
+    /// Name of the system in the profiler : its label if no other system shares it, its type name otherwise
+    fn name(&self) -> &'static str {
+        match self.label {
+            Some(label) if !self.shared_label => label,
+            _ => self.type_name,
+        }
+    }
+
+    fn run(&mut self, data: &mut GameData) {
+        match &mut self.system {
+            SystemRunner::Exclusive(system) => {
+                if !self.initialized {
+                    system.init(data);
+                    self.initialized = true;
+                }
+                system.run(data)
+            }
+            SystemRunner::Parallel(system) => system.run_shared(data),
+        }
+    }
+}
+
 pub(crate) struct Scheduler {
-    systems: LinkedList<(Option<fn(&GameState) -> bool>, fn(&mut GameData))>,
+    systems: Vec<SystemConfig>,
+    /// Indexes of the systems in execution order, computed when needed
+    order: Option<Vec<usize>>,
+    /// For each system, the systems that must be executed before it because of the ordering constraints
+    ancestors: Vec<HashSet<usize>>,
+    mode: ExecutionMode,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self { systems: vec![], order: None, ancestors: vec![], mode: ExecutionMode::Sequential }
+    }
 }
 
 impl Scheduler {
-    pub(crate) fn add_system(&mut self, system: fn(&mut GameData)) {
-        self.systems.push_back((None, system));
+    pub(crate) fn add_system<F: FnMut(&mut GameData) + Send + 'static>(&mut self, system: F) {
+        self.add_system_config(SystemConfigBuilder::new(system).get());
+    }
+
+    pub(crate) fn add_pausable_system<F: FnMut(&mut GameData) + Send + 'static>(&mut self,
+                                                                             system: F,
+                                                                             pause_condition: fn(&GameState) -> bool) {
+        self.add_system_config(SystemConfigBuilder::new(system).with_pause_condition(pause_condition).get());
     }
 
-    pub(crate) fn add_pausable_system(&mut self,
-                                      system: fn(&mut GameData),
-                                      pause_condition: fn(&GameState) -> bool) {
-        self.systems.push_back((Some(pause_condition), system));
+    pub(crate) fn add_system_config(&mut self, config: SystemConfig) {
+        self.systems.push(config);
+        self.order = None;
     }
 
+    /// Computes the execution order of the systems, stage by stage, respecting the `before` and `after` constraints.
+    /// Systems without constraints between them keep their registration order.
+    pub(crate) fn build(&mut self) -> Result<(), SchedulerError> {
+        let labels: Vec<Option<&'static str>> = self.systems.iter().map(|system| system.label).collect();
+        for system in self.systems.iter_mut() {
+            system.shared_label =
+                system.label.is_some() && labels.iter().filter(|label| **label == system.label).count() > 1;
+        }
+        let mut order = Vec::with_capacity(self.systems.len());
+        let mut ancestors = vec![HashSet::new(); self.systems.len()];
+        for stage in Stage::ALL {
+            order.append(&mut self.sort_stage(stage, &mut ancestors)?);
+        }
+        self.order = Some(order);
+        self.ancestors = ancestors;
+        Ok(())
+    }
+
+    pub(crate) fn set_execution_mode(&mut self, mode: ExecutionMode) {
+        self.mode = mode;
+    }
+
+    #[cfg(test)]
     pub(crate) fn execute(&mut self, data: &mut GameData) {
-        let systems_to_execute : LinkedList<&(Option<fn(&GameState) -> bool>, fn(&mut GameData))> = {
-          let game_state = data.get_resource::<GameState>().expect("Missing game state resource");
-            self.systems.iter().filter(|s| s.0.is_none() || !s.0.unwrap()(&game_state)).collect()
+        self.execute_with(data, |_, _| {});
+    }
+
+    /// Executes the systems stage by stage. `with_stage` is executed at the end of each stage, before the
+    /// world modifications queued in the [`crate::core::resources::commands::Commands`] are applied.
+    pub(crate) fn execute_with(&mut self, data: &mut GameData, mut with_stage: impl FnMut(Stage, &mut GameData)) {
+        for stage in Stage::ALL {
+            self.execute_stage(stage, data);
+            with_stage(stage, data);
+            // Sync point : the world modifications queued by the systems of the stage are visible to the next stages
+            apply_commands(data);
+        }
+    }
+
+    /// Executes the systems of `stage`, without applying the commands they queued
+    pub(crate) fn execute_stage(&mut self, stage: Stage, data: &mut GameData) {
+        if self.order.is_none() {
+            if let Err(e) = self.build() {
+                panic!("{}", e);
+            }
+        }
+        let stage_systems: Vec<usize> = {
+            let data: &GameData = data;
+            let game_state = data.get_resource::<GameState>().expect("Missing game state resource");
+            self.order
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|index| self.systems[*index].stage == stage)
+                .filter(|index| self.systems[*index].pause_condition.is_none_or(|condition| !condition(&game_state)))
+                .filter(|index| self.systems[*index].run_condition.as_ref().is_none_or(|condition| condition(data)))
+                .collect()
         };
+        if stage_systems.is_empty() {
+            return;
+        }
+
+        let profiling = profiling(data);
+        match self.mode {
+            ExecutionMode::Sequential => stage_systems.iter().for_each(|index| self.execute_system(*index, data, profiling)),
+            ExecutionMode::Parallel => {
+                for batch in self.batches(&stage_systems) {
+                    if batch.len() == 1 {
+                        self.execute_system(batch[0], data, profiling);
+                    } else {
+                        self.execute_batch(&batch, data, profiling);
+                    }
+                }
+            }
+        }
+    }
+
+    fn execute_system(&mut self, index: usize, data: &mut GameData, profiling: bool) {
+        let config = &mut self.systems[index];
+        if profiling {
+            let start = Instant::now();
+            config.run(data);
+            record(data, config.name(), ProfileCategory::System, MAIN_THREAD, start, start.elapsed());
+        } else {
+            config.run(data);
+        }
+    }
+
+    /// Splits the ordered systems in batches of systems that can be executed at the same time
+    fn batches(&self, systems: &[usize]) -> Vec<Vec<usize>> {
+        let mut batches: Vec<Vec<usize>> = vec![];
+        let mut batch_access = SystemAccess::default();
+        for index in systems.iter() {
+            let config = &self.systems[*index];
+            let joinable = match (batches.last(), config.parallel_access()) {
+                (Some(batch), Some(access)) => batch.iter().all(|other| {
+                    let other_config = &self.systems[*other];
+                    other_config.stage == config.stage
+                        && other_config.parallel_access().is_some()
+                        && !self.ancestors[*index].contains(other)
+                }) && !batch_access.conflicts_with(access),
+                _ => false,
+            };
+            if !joinable {
+                batches.push(vec![]);
+                batch_access = SystemAccess::default();
+            }
+            if let Some(access) = config.parallel_access() {
+                batch_access.extend(access);
+            }
+            batches.last_mut().expect("A batch has just been added").push(*index);
+        }
+        batches
+    }
+
+    /// Executes each system of the batch on its own scoped thread, and returns once they all ended
+    fn execute_batch(&mut self, batch: &[usize], data: &mut GameData, profiling: bool) {
+        let data: &GameData = data;
+        let timings: Vec<(&'static str, Instant, Duration)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .systems
+                .iter_mut()
+                .enumerate()
+                .filter(|(index, _)| batch.contains(index))
+                .filter_map(|(_, config)| {
+                    let name = config.name();
+                    match &mut config.system {
+                        SystemRunner::Parallel(system) => Some(scope.spawn(move || {
+                            let start = Instant::now();
+                            system.run_shared(data);
+                            (name, start, start.elapsed())
+                        })),
+                        SystemRunner::Exclusive(_) => None,
+                    }
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                .collect()
+        });
+        if profiling {
+            for (lane, (name, start, duration)) in timings.into_iter().enumerate() {
+                record(data, name, ProfileCategory::System, FIRST_WORKER_THREAD + lane, start, duration);
+            }
+        }
+    }
+
+    /// Systems of the stage of the system `index` labelled `label`. A constraint against systems of other stages
+    /// is satisfied by the stages order, or can't be and fails.
+    fn labelled(&self, index: usize, label: &'static str, before: bool) -> Result<Vec<usize>, SchedulerError> {
+        let stage = self.systems[index].stage;
+        let mut matching = vec![];
+        for (other, system) in self.systems.iter().enumerate().filter(|(_, system)| system.label == Some(label)) {
+            if system.stage == stage {
+                matching.push(other);
+            } else if (system.stage < stage) == before {
+                return Err(SchedulerError::UnsatisfiableConstraint {
+                    stage,
+                    system: self.display_name(index),
+                    label: label.to_string(),
+                });
+            }
+        }
+        if matching.is_empty() && self.systems.iter().all(|system| system.label != Some(label)) {
+            warn!("Constraint on unknown system label `{}` from stage {:?}, it is ignored", label, stage);
+        }
+        Ok(matching)
+    }
+
+    fn display_name(&self, index: usize) -> String {
+        match self.systems[index].label {
+            Some(label) => label.to_string(),
+            None => format!("<unlabelled system #{}>", index),
+        }
+    }
+
+    fn sort_stage(&self, stage: Stage, ancestors: &mut [HashSet<usize>]) -> Result<Vec<usize>, SchedulerError> {
+        let indexes: Vec<usize> = (0..self.systems.len()).filter(|i| self.systems[*i].stage == stage).collect();
+
+        // edges[i] contains the systems that must run after the system i
+        let mut edges: Vec<HashSet<usize>> = vec![HashSet::new(); self.systems.len()];
+        for index in indexes.iter() {
+            let system = &self.systems[*index];
+            for label in system.before.iter() {
+                self.labelled(*index, label, true)?.into_iter().for_each(|next| {
+                    edges[*index].insert(next);
+                });
+            }
+            for label in system.after.iter() {
+                self.labelled(*index, label, false)?.into_iter().for_each(|previous| {
+                    edges[previous].insert(*index);
+                });
+            }
+        }
+
+        let mut incoming = vec![0; self.systems.len()];
+        edges.iter().flatten().for_each(|next| incoming[*next] += 1);
+
+        let mut ready: BinaryHeap<Reverse<usize>> =
+            indexes.iter().filter(|i| incoming[**i] == 0).map(|i| Reverse(*i)).collect();
+        let mut sorted = Vec::with_capacity(indexes.len());
+        while let Some(Reverse(index)) = ready.pop() {
+            sorted.push(index);
+            for next in edges[index].iter() {
+                incoming[*next] -= 1;
+                if incoming[*next] == 0 {
+                    ready.push(Reverse(*next));
+                }
+            }
+        }
+
+        if sorted.len() == indexes.len() {
+            for index in sorted.iter() {
+                for next in edges[*index].iter() {
+                    let mut inherited = ancestors[*index].clone();
+                    inherited.insert(*index);
+                    ancestors[*next].extend(inherited);
+                }
+            }
+        } else {
+            let systems = indexes.iter().filter(|i| incoming[**i] > 0).map(|i| self.display_name(*i)).collect();
+            return Err(SchedulerError::CycleDetected { stage, systems });
+        }
+        Ok(sorted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::core::resources::commands::Commands;
+    use crate::core::resources::profiler::Profiler;
+    use crate::core::system_param::{Res, ResMut};
+    use crate::core::world::World;
+
+    use super::*;
+
+    fn log(data: &mut GameData, value: &str) {
+        let log = data.game_state().get_text("log").unwrap_or_default();
+        data.game_state_mut().set_text("log", &format!("{}{}", log, value));
+    }
+
+    fn a(data: &mut GameData) {
+        log(data, "a");
+    }
+
+    fn b(data: &mut GameData) {
+        log(data, "b");
+    }
+
+    fn c(data: &mut GameData) {
+        log(data, "c");
+    }
+
+    fn execute(scheduler: &mut Scheduler) -> String {
+        let mut data = GameData::default();
+        data.insert_resource(GameState::default());
+        scheduler.execute(&mut data);
+        let log = data.game_state().get_text("log").unwrap_or_default();
+        log
+    }
+
+    #[test]
+    fn stages_and_constraints_order_test() {
+        let mut scheduler = Scheduler::default();
+        scheduler.add_system_config(SystemConfigBuilder::new(a).with_stage(Stage::PostUpdate).get());
+        scheduler.add_system(b);
+        scheduler.add_system_config(SystemConfigBuilder::new(c).with_label("c").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(a).before("c").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(b).with_stage(Stage::PreUpdate).get());
+
+        assert_eq!("bbaca", execute(&mut scheduler));
+    }
+
+    #[test]
+    fn after_constraint_test() {
+        let mut scheduler = Scheduler::default();
+        scheduler.add_system_config(SystemConfigBuilder::new(a).after("b").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(b).with_label("b").after("c").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(c).with_label("c").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(a).after("unknown").get());
+
+        assert_eq!("cbaa", execute(&mut scheduler));
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        initial: usize,
+        runs: usize,
+    }
+
+    impl System for Counter {
+        fn init(&mut self, _data: &mut GameData) {
+            self.initial = 10;
+        }
+
+        fn run(&mut self, data: &mut GameData) {
+            self.runs += 1;
+            log(data, &(self.initial + self.runs).to_string());
+        }
+    }
+
+    #[test]
+    fn stateful_systems_test() {
+        let mut scheduler = Scheduler::default();
+        let mut calls = 0;
+        scheduler.add_system(move |data: &mut GameData| {
+            calls += 1;
+            log(data, &calls.to_string());
+        });
+        scheduler.add_system_config(SystemConfigBuilder::new(Counter::default()).get());
+
+        let mut data = GameData::default();
+        data.insert_resource(GameState::default());
+        scheduler.execute(&mut data);
+        scheduler.execute(&mut data);
+        assert_eq!("111212", data.game_state().get_text("log").unwrap());
+    }
+
+    #[test]
+    fn cycle_detection_test() {
+        let mut scheduler = Scheduler::default();
+        scheduler.add_system_config(SystemConfigBuilder::new(a).with_label("a").after("c").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(b).with_label("b").after("a").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(c).with_label("c").after("b").get());
+        scheduler.add_system(c);
+
+        assert_eq!(
+            Err(SchedulerError::CycleDetected {
+                stage: Stage::Update,
+                systems: vec!["a".to_string(), "b".to_string(), "c".to_string()]
+            }),
+            scheduler.build()
+        );
+    }
+    #[test]
+    fn unsatisfiable_constraint_test() {
+        let mut scheduler = Scheduler::default();
+        scheduler.add_system_config(SystemConfigBuilder::new(a).with_label("a").with_stage(Stage::PreUpdate).get());
+        scheduler.add_system_config(SystemConfigBuilder::new(b).with_label("b").after("a").before("c").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(c).with_label("c").with_stage(Stage::PostUpdate).get());
+        assert!(scheduler.build().is_ok());
+
+        scheduler.add_system_config(SystemConfigBuilder::new(c).before("a").get());
+        assert_eq!(
+            Err(SchedulerError::UnsatisfiableConstraint {
+                stage: Stage::Update,
+                system: "<unlabelled system #3>".to_string(),
+                label: "a".to_string()
+            }),
+            scheduler.build()
+        );
+    }
+
+    #[test]
+    fn profiler_names_test() {
+        let mut scheduler = Scheduler::default();
+        scheduler.add_system_config(SystemConfigBuilder::new(a).with_label("shared").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(b).with_label("shared").get());
+        scheduler.add_system_config(SystemConfigBuilder::new(c).with_label("c").get());
+        let mut data = GameData::default();
+        data.insert_resource(GameState::default());
+        let mut profiler = Profiler::default();
+        profiler.enable();
+        data.insert_resource(profiler);
+
+        scheduler.execute(&mut data);
+        let profiler = data.get_resource::<Profiler>().unwrap();
+        assert_eq!(1, profiler.stats("scion::core::scheduler::tests::a").unwrap().calls());
+        assert_eq!(1, profiler.stats("scion::core::scheduler::tests::b").unwrap().calls());
+        assert_eq!(1, profiler.stats("c").unwrap().calls());
+        assert!(profiler.stats("shared").is_none());
+    }
+
+    #[test]
+    fn commands_applied_between_stages_test() {
+        let mut scheduler = Scheduler::default();
+        scheduler.add_system(|data: &mut GameData| data.commands().spawn((1,)));
+        scheduler.add_system(|data: &mut GameData| {
+            let count = data.query::<&i32>().iter().count();
+            log(data, &count.to_string());
+        });
+        scheduler.add_system_config(
+            SystemConfigBuilder::new(|data: &mut GameData| {
+                let count = data.query::<&i32>().iter().count();
+                log(data, &count.to_string());
+            })
+            .with_stage(Stage::PostUpdate)
+            .get(),
+        );
+        let mut data = GameData::default();
+        data.insert_resource(GameState::default());
+        data.insert_resource(Commands::default());
+
+        scheduler.execute(&mut data);
+        assert_eq!("01", data.game_state().get_text("log").unwrap());
+    }
+
+    #[derive(Default)]
+    struct Score(usize);
+
+    #[derive(Default)]
+    struct Lives(usize);
+
+    fn read_score(_score: Res<Score>) {}
+
+    fn add_score(mut score: ResMut<Score>) {
+        score.0 += 1;
+    }
+
+    fn remove_life(mut lives: ResMut<Lives>) {
+        lives.0 -= 1;
+    }
+
+    fn typed<F: SystemParamFunction<P>, P: 'static>(function: F) -> SystemConfigBuilder {
+        SystemConfigBuilder::typed(function).unwrap()
+    }
+
+    fn parallel_scheduler(configs: Vec<SystemConfig>) -> Scheduler {
+        let mut scheduler = Scheduler::default();
+        scheduler.set_execution_mode(ExecutionMode::Parallel);
+        configs.into_iter().for_each(|config| scheduler.add_system_config(config));
+        scheduler.build().unwrap();
+        scheduler
+    }
+
+    #[test]
+    fn batches_by_access_test() {
+        let scheduler = parallel_scheduler(vec![
+            typed(add_score).get(),
+            typed(remove_life).get(),
+            typed(read_score).get(),
+            typed(read_score).get(),
+            typed(remove_life).exclusive().get(),
+            SystemConfigBuilder::new(a).get(),
+            typed(read_score).with_stage(Stage::PostUpdate).get(),
+        ]);
+
+        let order = scheduler.order.clone().unwrap();
+        assert_eq!(vec![vec![0, 1], vec![2, 3], vec![4], vec![5], vec![6]], scheduler.batches(&order));
+    }
+
+    #[test]
+    fn batches_respect_constraints_test() {
+        let scheduler = parallel_scheduler(vec![
+            typed(add_score).with_label("score").get(),
+            typed(read_score).get(),
+            typed(remove_life).after("score").get(),
+        ]);
+
+        let order = scheduler.order.clone().unwrap();
+        assert_eq!(vec![vec![0], vec![1, 2]], scheduler.batches(&order));
+    }
+
+    #[test]
+    fn parallel_execution_test() {
+        let mut scheduler = parallel_scheduler(vec![
+            typed(add_score).get(),
+            typed(remove_life).get(),
+            typed(add_score).get(),
+        ]);
+        let mut data = GameData::default();
+        data.insert_resource(GameState::default());
+        data.insert_resource(Score(0));
+        data.insert_resource(Lives(3));
+
+        scheduler.execute(&mut data);
+        scheduler.execute(&mut data);
+
+        assert_eq!(4, data.get_resource::<Score>().unwrap().0);
+        assert_eq!(1, data.get_resource::<Lives>().unwrap().0);
+    }
+
+    /// Number of systems that reached the meeting point, and number of systems that saw the other one there
+    #[derive(Default)]
+    struct MeetingPoint {
+        arrived: AtomicUsize,
+        met: AtomicUsize,
+    }
+
+    /// Waits for the other system at the meeting point, which only happens if both are running at the same time
+    fn meet(meeting_point: Res<MeetingPoint>) {
+        meeting_point.arrived.fetch_add(1, Ordering::SeqCst);
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            if meeting_point.arrived.load(Ordering::SeqCst) == 2 {
+                meeting_point.met.fetch_add(1, Ordering::SeqCst);
+                return;
+            }
+            std::thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn systems_run_at_the_same_time_test() {
+        let mut scheduler = parallel_scheduler(vec![typed(meet).get(), typed(meet).get()]);
+        let mut data = GameData::default();
+        data.insert_resource(GameState::default());
+        data.insert_resource(MeetingPoint::default());
+
+        scheduler.execute(&mut data);
 
-        systems_to_execute.iter().for_each(|s| s.1(data))
+        assert_eq!(2, data.get_resource::<MeetingPoint>().unwrap().met.load(Ordering::SeqCst));
     }
 }
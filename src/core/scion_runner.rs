@@ -1,22 +1,35 @@
 use std::sync::{Arc, mpsc};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::Receiver;
 use std::thread;
-use std::time::Instant;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
+use atomic_refcell::AtomicRefMut;
+use log::info;
 use winit::dpi::{PhysicalSize, Size};
+use winit::event_loop::EventLoopProxy;
 use winit::window::Window;
 
-use crate::core::resources::time::Time;
+use crate::core::app_state::apply_app_state_transitions;
+use crate::core::resources::audio::Audio;
+use crate::core::resources::commands::apply_commands;
+use crate::core::resources::profiler::{profile, profiling, record, ProfileCategory, RENDER_THREAD};
+use crate::core::resources::save_manager::auto_save;
+use crate::core::resources::time::{update_timers, Time};
 use crate::core::scene::{SceneAction, SceneMachine};
 use crate::core::scheduler::Scheduler;
+use crate::core::state::publish_game_state_changes;
+use crate::core::systems::interpolation_system::interpolation_snapshot_system;
 use crate::core::world::GameData;
 use crate::graphics::rendering::{RendererEvent, RenderingInfos, RenderingUpdate};
 use crate::graphics::rendering::scion2d::pre_renderer::Scion2DPreRenderer;
 use crate::graphics::rendering::scion2d::rendering_thread::ScionRenderingThread;
 use crate::graphics::rendering::scion2d::window_rendering_manager::ScionWindowRenderingManager;
 use crate::graphics::windowing::window_event_handler::handle_window_event;
-use crate::graphics::windowing::WindowingEvent;
-use crate::utils::frame_limiter::{FrameLimiter, FrameLimiterConfig};
+use crate::graphics::windowing::{WindowCommand, WindowingEvent};
+use crate::config::frame_limiter_config::FrameLimiterConfig;
+use crate::utils::frame_limiter::FrameLimiter;
 
 pub struct ScionRunner {
     pub(crate) game_data: GameData,
@@ -25,84 +38,202 @@ pub struct ScionRunner {
     pub(crate) window_rendering_manager: Option<ScionWindowRenderingManager>,
     pub(crate) window: Option<Arc<Window>>,
     pub(crate) main_thread_receiver: Option<Receiver<WindowingEvent>>,
+    pub(crate) main_thread_proxy: Option<EventLoopProxy<WindowCommand>>,
     pub(crate) scion_pre_renderer: Scion2DPreRenderer,
+    pub(crate) frame_limiter_config: FrameLimiterConfig,
 }
 
 impl ScionRunner {
     pub(crate) fn launch_game_loop(mut self) {
         self.setup();
-        let mut frame_limiter = FrameLimiter::new(FrameLimiterConfig::default());
-        let (render_sender, render_receiver) = mpsc::channel::<(Vec<RendererEvent>, Vec<RenderingUpdate>, Vec<RenderingInfos>)>();
-        let window_rendering_manager = self.window_rendering_manager.take();
-
-        thread::spawn(move || { ScionRenderingThread::new(window_rendering_manager, render_receiver).run() });
+        let mut frame_limiter = FrameLimiter::new(&self.frame_limiter_config);
+        self.time().set_fixed_delta_duration(frame_limiter.fixed_timestep().fixed_duration());
+        let profiler_enabled = Arc::new(AtomicBool::new(false));
+        let rendering_thread = self.window_rendering_manager.take().map(|window_rendering_manager| {
+            let (render_sender, render_receiver) = mpsc::channel::<(Vec<RendererEvent>, Vec<RenderingUpdate>, Vec<RenderingInfos>)>();
+            let (timings_sender, timings_receiver) = mpsc::channel::<(&'static str, Instant, Duration)>();
+            let render_profiling = profiler_enabled.clone();
+            let handle = thread::spawn(move || {
+                ScionRenderingThread::new(Some(window_rendering_manager), render_receiver, timings_sender, render_profiling).run()
+            });
+            (render_sender, timings_receiver, handle)
+        });
+        let render_sender = rendering_thread.as_ref().map(|(render_sender, _, _)| render_sender);
+        let timings_receiver = rendering_thread.as_ref().map(|(_, timings_receiver, _)| timings_receiver);
 
         let mut start_tick = Instant::now();
-        let mut fixed_tick = Instant::now();
-        let mut render_tick = Instant::now();
 
         loop {
             let should_tick = frame_limiter.is_min_tick();
             if should_tick {
                 start_tick = Instant::now();
-                let frame_duration = self
-                    .game_data
-                    .get_resource_mut::<Time>()
-                    .expect("Time is an internal resource and can't be missing")
-                    .frame();
-                self.game_data.timers().add_delta_duration(frame_duration);
-                let _r = render_sender.send((handle_window_event(&mut self), vec![], vec![]));
-                self.layer_machine.apply_scene_action(SceneAction::Update, &mut self.game_data);
-                self.scheduler.execute(&mut self.game_data);
-                self.layer_machine.apply_scene_action(SceneAction::LateUpdate, &mut self.game_data);
-                self.update_cursor();
+                let frame_duration = self.time().frame();
+                let window_events = handle_window_event(&mut self);
+                if let Some(render_sender) = render_sender {
+                    let _r = render_sender.send((window_events, vec![], vec![]));
+                }
+                self.update(frame_duration);
             }
 
-            if frame_limiter.is_fixed_update() {
-                fixed_tick = Instant::now();
-                self.layer_machine.apply_scene_action(SceneAction::FixedUpdate, &mut self.game_data);
-                frame_limiter.fixed_tick();
+            for _ in 0..frame_limiter.fixed_updates() {
+                self.fixed_update();
             }
+            self.time().set_interpolation_alpha(frame_limiter.fixed_timestep().alpha());
 
             if frame_limiter.render_unlocked() {
-                render_tick = Instant::now();
-
-                let updates = self.scion_pre_renderer.prepare_update(&mut self.game_data);
-                let rendering_infos = Scion2DPreRenderer::prepare_rendering(&mut self.game_data);
-                let _r = render_sender.send((vec![], updates, rendering_infos));
-
+                profiler_enabled.store(profiling(&self.game_data), Ordering::Relaxed);
+                if let Some(render_sender) = render_sender {
+                    let pre_renderer = &mut self.scion_pre_renderer;
+                    let updates = profile(&mut self.game_data, ProfileCategory::Render, || "prepare_update".to_string(),
+                                          |data| pre_renderer.prepare_update(data));
+                    let rendering_infos = profile(&mut self.game_data, ProfileCategory::Render, || "prepare_rendering".to_string(),
+                                                  Scion2DPreRenderer::prepare_rendering);
+                    let _r = render_sender.send((vec![], updates, rendering_infos));
+                }
+                if let Some(timings_receiver) = timings_receiver {
+                    while let Ok((name, start, duration)) = timings_receiver.try_recv() {
+                        record(&self.game_data, name, ProfileCategory::Render, RENDER_THREAD, start, duration);
+                    }
+                }
                 frame_limiter.render();
             }
 
             if should_tick {
-                self.game_data.inputs().reset_inputs();
-                self.game_data.events().cleanup();
-                self.layer_machine
-                    .apply_scene_action(SceneAction::EndFrame, &mut self.game_data);
+                self.end_frame();
                 frame_limiter.tick(&start_tick);
+                if self.game_data.quit_requested() {
+                    break;
+                }
             }
-            thread::sleep(frame_limiter.min_tick_duration.clone());
+            thread::sleep(frame_limiter.sleep_duration());
         }
+        self.shutdown(rendering_thread.map(|(render_sender, _, handle)| {
+            drop(render_sender);
+            handle
+        }));
     }
 
     pub(crate) fn setup(&mut self) {
-        self.game_data.insert_resource(crate::core::resources::window::Window::new(
-            (self.window.as_ref().unwrap().inner_size().width, self.window.as_ref().unwrap().inner_size().height),
-            self.window.as_ref().unwrap().scale_factor(),
-        ));
+        let window = match self.window.as_ref() {
+            Some(window) => crate::core::resources::window::Window::new(
+                (window.inner_size().width, window.inner_size().height),
+                window.scale_factor(),
+            ),
+            None => crate::core::resources::window::Window::new((0, 0), 1.0),
+        };
+        self.game_data.insert_resource(window);
+        apply_app_state_transitions(&mut self.game_data);
         self.layer_machine.apply_scene_action(SceneAction::Start, &mut self.game_data);
     }
+
+    /// Runs the variable part of a frame : replayed events, timers, scene update, systems and scene systems stage by stage, and scene late update.
+    pub(crate) fn update(&mut self, frame_duration: Duration) {
+        self.game_data.events().start_frame();
+        update_timers(&mut self.game_data, frame_duration);
+        self.layer_machine.apply_scene_action(SceneAction::Update, &mut self.game_data);
+        let layer_machine = &mut self.layer_machine;
+        self.scheduler.execute_with(&mut self.game_data, |stage, data| layer_machine.execute_systems(stage, data));
+        self.layer_machine.apply_scene_action(SceneAction::LateUpdate, &mut self.game_data);
+        self.update_cursor();
+    }
+
+    pub(crate) fn fixed_update(&mut self) {
+        interpolation_snapshot_system(&mut self.game_data);
+        self.layer_machine.apply_scene_action(SceneAction::FixedUpdate, &mut self.game_data);
+    }
+
+    /// Applies the pending commands, clears the frame scoped data (inputs, events) and applies the pending scene actions.
+    pub(crate) fn end_frame(&mut self) {
+        apply_app_state_transitions(&mut self.game_data);
+        apply_commands(&mut self.game_data);
+        self.game_data.subworld.changes.end_frame();
+        self.game_data.inputs().reset_inputs();
+        publish_game_state_changes(&self.game_data);
+        self.game_data.events().cleanup();
+        self.layer_machine.apply_scene_action(SceneAction::EndFrame, &mut self.game_data);
+    }
+
+    /// Auto-saves the game, stops the current scene, waits for the rendering and audio threads to end and notifies
+    /// the main thread.
+    pub(crate) fn shutdown(&mut self, rendering_thread: Option<JoinHandle<()>>) {
+        info!("Shutting down the game loop");
+        auto_save(&self.game_data);
+        self.layer_machine.stop(&mut self.game_data);
+        if let Some(handle) = rendering_thread {
+            let _r = handle.join();
+        }
+        if let Some(audio) = self.game_data.remove_resource::<Audio>() {
+            audio.shutdown();
+        }
+        if let Some(proxy) = self.main_thread_proxy.take() {
+            let _r = proxy.send_event(WindowCommand::Exit);
+        }
+    }
+
+    pub(crate) fn time(&self) -> AtomicRefMut<'_, Time> {
+        self.game_data.get_resource_mut::<Time>().expect("Time is an internal resource and can't be missing")
+    }
+
     fn update_cursor(&mut self) {
         let mut window = self.game_data.window();
-        if let Some(icon) = window.new_cursor() {
-            let w = self.window.as_mut().expect("A window is mandatory to run this game !");
-            w.set_cursor_icon(*icon);
-        }
-        if let Some(dimensions) = window.new_dimensions() {
-            let w = self.window.as_mut().expect("A window is mandatory to run this game !");
-            let _r = w.request_inner_size(Size::Physical(PhysicalSize::new(dimensions.0 * window.dpi() as u32,
-                                                                           dimensions.1 * window.dpi() as u32)));
+        if let Some(w) = self.window.as_mut() {
+            if let Some(icon) = window.new_cursor() {
+                w.set_cursor_icon(*icon);
+            }
+            if let Some(dimensions) = window.new_dimensions() {
+                let _r = w.request_inner_size(Size::Physical(PhysicalSize::new(dimensions.0 * window.dpi() as u32,
+                                                                               dimensions.1 * window.dpi() as u32)));
+            }
         }
         window.reset_future_settings()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use winit::window::CursorIcon;
+
+    use crate::core::resources::audio::PlayConfig;
+    use crate::core::scene::{Scene, SceneController};
+    use crate::core::state::GameState;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct StartScene;
+
+    impl Scene for StartScene {
+        fn on_start(&mut self, data: &mut GameData) {
+            data.game_state_mut().set_bool("started", true);
+        }
+    }
+
+    #[test]
+    fn headless_runner_setup_test() {
+        let mut game_data = GameData::default();
+        game_data.insert_resource(GameState::default());
+        game_data.insert_resource(SceneController::default());
+        game_data.insert_resource(Audio::disabled());
+        let mut runner = ScionRunner {
+            game_data,
+            scheduler: Default::default(),
+            layer_machine: SceneMachine::new(Some(Box::new(StartScene))),
+            window_rendering_manager: None,
+            window: None,
+            main_thread_receiver: None,
+            main_thread_proxy: None,
+            scion_pre_renderer: Default::default(),
+            frame_limiter_config: Default::default(),
+        };
+
+        runner.setup();
+        assert!(runner.game_data.game_state().get_bool("started"));
+        assert_eq!((0, 0), runner.game_data.window().dimensions());
+
+        runner.game_data.window().set_cursor(CursorIcon::Pointer);
+        runner.update_cursor();
+        assert!(runner.game_data.window().new_cursor().is_none());
+
+        assert!(runner.game_data.audio().play("missing.ogg".to_string(), PlayConfig::default()).is_ok());
+    }
+}
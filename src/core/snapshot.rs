@@ -0,0 +1,605 @@
+//! Serialization of the world into a [`WorldSnapshot`], to restore it later.
+//!
+//! Only the components and resources registered in the [`SnapshotRegistry`] resource are part of a snapshot, the
+//! others are dropped. `Scion` registers its own serializable components and the [`GameState`] and [`Timers`]
+//! resources, custom ones can be added with [`SnapshotRegistry::register_component`] and
+//! [`SnapshotRegistry::register_resource`].
+//!
+//! Entities are given new ids when a snapshot is restored. Components referencing other entities, like [`Parent`]
+//! or [`Children`], implement [`MapEntities`] to be updated with the new ids. References to entities that are not
+//! part of the snapshot, like the excluded ones, are dropped.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+
+use hecs::{Component, Entity, EntityBuilder};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::core::components::maths::camera::{Camera, DefaultCamera};
+use crate::core::components::maths::collider::{Collider, ColliderDebug};
+use crate::core::components::maths::hierarchy::{Children, Parent};
+use crate::core::components::maths::transform::Transform;
+use crate::core::components::name::{Name, Tags};
+use crate::core::resources::asset_manager::AssetRef;
+use crate::core::resources::time::Timers;
+use crate::core::state::GameState;
+use crate::core::world::{GameData, Resource, World};
+use crate::graphics::components::animations::Animations;
+use crate::graphics::components::material::Material;
+use crate::graphics::components::tiles::sprite::Sprite;
+use crate::graphics::components::ui::ui_text::{UiText, UiTextImage};
+use crate::graphics::components::Hide;
+
+const BINARY_MAGIC: &[u8; 4] = b"SCNS";
+const BINARY_VERSION: u8 = 1;
+
+/// Errors that can happen while taking or restoring a snapshot
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The registered component or resource `name` could not be serialized or deserialized
+    Serialization { name: String, message: String },
+    /// The given data is not a valid snapshot
+    InvalidFormat(String),
+    /// The snapshot contains a component or resource `name` that is not registered
+    Unregistered(String),
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Serialization { name, message } => write!(f, "Error while serializing `{}`: {}", name, message),
+            SnapshotError::InvalidFormat(message) => write!(f, "Invalid snapshot: {}", message),
+            SnapshotError::Unregistered(name) => write!(f, "`{}` is not registered in the snapshot registry", name),
+        }
+    }
+}
+
+/// Components referencing entities, to update when the entities are given new ids by a restore
+pub trait MapEntities {
+    /// Replaces the referenced entities with their new ids. Returns `false` when the component can't be restored
+    /// without an entity missing from the snapshot, the component is then dropped.
+    fn map_entities(&mut self, map: &EntityMap) -> bool;
+}
+
+/// Ids given to the entities of a snapshot when it was restored
+#[derive(Debug, Default)]
+pub struct EntityMap {
+    entities: HashMap<Entity, Entity>,
+}
+
+impl EntityMap {
+    /// New id of the snapshot entity `entity`, if it was part of the snapshot
+    pub fn get(&self, entity: Entity) -> Option<Entity> {
+        self.entities.get(&entity).copied()
+    }
+}
+
+/// Serialized entities and resources of a world
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    entities: Vec<EntitySnapshot>,
+    resources: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EntitySnapshot {
+    id: u64,
+    components: BTreeMap<String, Value>,
+}
+
+impl WorldSnapshot {
+    /// Number of entities in the snapshot
+    pub fn entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    pub fn to_json(&self) -> Result<String, SnapshotError> {
+        serde_json::to_string(self).map_err(|e| SnapshotError::InvalidFormat(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        serde_json::from_str(json).map_err(|e| SnapshotError::InvalidFormat(e.to_string()))
+    }
+
+    /// Binary representation of the snapshot, read back without any text parsing
+    pub fn to_binary(&self) -> Result<Vec<u8>, SnapshotError> {
+        let value = serde_json::to_value(self).map_err(|e| SnapshotError::InvalidFormat(e.to_string()))?;
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.push(BINARY_VERSION);
+        binary::write(&value, &mut bytes);
+        Ok(bytes)
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        if bytes.len() < 5 || &bytes[..4] != BINARY_MAGIC {
+            return Err(SnapshotError::InvalidFormat("missing binary snapshot header".to_string()));
+        }
+        if bytes[4] != BINARY_VERSION {
+            return Err(SnapshotError::InvalidFormat(format!("unsupported binary version {}", bytes[4])));
+        }
+        let mut reader = binary::Reader { bytes: &bytes[5..], depth: 0 };
+        let value = reader.read().map_err(|message| SnapshotError::InvalidFormat(message.to_string()))?;
+        if !reader.bytes.is_empty() {
+            return Err(SnapshotError::InvalidFormat("trailing bytes after the binary snapshot".to_string()));
+        }
+        serde_json::from_value(value).map_err(|e| SnapshotError::InvalidFormat(e.to_string()))
+    }
+}
+
+type PendingComponent = Box<dyn FnOnce(&EntityMap, &mut EntityBuilder)>;
+type PendingResource = Box<dyn FnOnce(&mut GameData)>;
+
+#[derive(Clone)]
+struct ComponentRegistration {
+    name: String,
+    serialize: fn(&GameData, Entity) -> Option<serde_json::Result<Value>>,
+    deserialize: fn(Value) -> serde_json::Result<PendingComponent>,
+}
+
+#[derive(Clone)]
+struct ResourceRegistration {
+    name: String,
+    serialize: fn(&GameData) -> Option<serde_json::Result<Value>>,
+    deserialize: fn(Value) -> serde_json::Result<PendingResource>,
+}
+
+/// `SnapshotRegistry` is the resource listing the components and resources that are part of the snapshots,
+/// with the name used to store them.
+#[derive(Clone)]
+pub struct SnapshotRegistry {
+    components: Vec<ComponentRegistration>,
+    resources: Vec<ResourceRegistration>,
+    excluded: Vec<fn(&GameData, Entity) -> bool>,
+}
+
+impl Default for SnapshotRegistry {
+    fn default() -> Self {
+        let mut registry = Self { components: vec![], resources: vec![], excluded: vec![] };
+        registry.register_component_with_entities::<Parent>("parent");
+        registry.register_component_with_entities::<Children>("children");
+        registry.register_component_with_entities::<Collider>("collider");
+        registry.register_component::<Name>("name");
+        registry.register_component::<Tags>("tags");
+        registry.register_component::<Transform>("transform");
+        registry.register_component::<Camera>("camera");
+        registry.register_component::<DefaultCamera>("default_camera");
+        registry.register_component::<Sprite>("sprite");
+        registry.register_component::<Material>("material");
+        registry.register_component::<AssetRef<Material>>("material_ref");
+        registry.register_component::<UiText>("ui_text");
+        registry.register_component::<Animations>("animations");
+        registry.register_component::<Hide>("hide");
+        registry.register_resource::<GameState>("game_state");
+        registry.register_resource::<Timers>("timers");
+        // Generated again by the engine systems from the components they belong to
+        registry.exclude::<UiTextImage>();
+        registry.exclude::<ColliderDebug>();
+        registry
+    }
+}
+
+impl SnapshotRegistry {
+    /// Registers the component `T` under `name`, replacing any registration with the same name
+    pub fn register_component<T: Component + Serialize + DeserializeOwned>(&mut self, name: &str) {
+        self.add_component(ComponentRegistration {
+            name: name.to_string(),
+            serialize: serialize_component::<T>,
+            deserialize: |value| {
+                let component = serde_json::from_value::<T>(value)?;
+                Ok(Box::new(move |_, builder: &mut EntityBuilder| {
+                    builder.add(component);
+                }))
+            },
+        });
+    }
+
+    /// Registers the component `T`, which references entities, under `name`
+    pub fn register_component_with_entities<T: Component + Serialize + DeserializeOwned + MapEntities>(
+        &mut self,
+        name: &str,
+    ) {
+        self.add_component(ComponentRegistration {
+            name: name.to_string(),
+            serialize: serialize_component::<T>,
+            deserialize: |value| {
+                let mut component = serde_json::from_value::<T>(value)?;
+                Ok(Box::new(move |map: &EntityMap, builder: &mut EntityBuilder| {
+                    if component.map_entities(map) {
+                        builder.add(component);
+                    }
+                }))
+            },
+        });
+    }
+
+    /// Registers the resource `R` under `name`, replacing any registration with the same name
+    pub fn register_resource<R: Resource + Serialize + DeserializeOwned>(&mut self, name: &str) {
+        self.resources.retain(|registration| registration.name != name);
+        self.resources.push(ResourceRegistration {
+            name: name.to_string(),
+            serialize: |data| data.get_resource::<R>().map(|resource| serde_json::to_value(&*resource)),
+            deserialize: |value| {
+                let resource = serde_json::from_value::<R>(value)?;
+                Ok(Box::new(move |data: &mut GameData| data.insert_resource(resource)))
+            },
+        });
+    }
+
+    /// Excludes the entities having a `T` from the snapshots
+    pub fn exclude<T: Component>(&mut self) {
+        self.excluded.push(|data, entity| data.entry::<&T>(entity).is_ok_and(|mut entry| entry.get().is_some()));
+    }
+
+    fn add_component(&mut self, registration: ComponentRegistration) {
+        self.components.retain(|r| r.name != registration.name);
+        self.components.push(registration);
+    }
+}
+
+fn serialize_component<T: Component + Serialize>(data: &GameData, entity: Entity) -> Option<serde_json::Result<Value>> {
+    let mut entry = data.entry::<&T>(entity).ok()?;
+    let value = entry.get().map(serde_json::to_value);
+    value
+}
+
+fn registry(data: &GameData) -> SnapshotRegistry {
+    data.get_resource::<SnapshotRegistry>().map(|registry| registry.clone()).unwrap_or_default()
+}
+
+fn serialization_error(name: &str, error: serde_json::Error) -> SnapshotError {
+    SnapshotError::Serialization { name: name.to_string(), message: error.to_string() }
+}
+
+/// Serializes the registered components of the entities and the registered resources
+pub(crate) fn take_snapshot(data: &GameData) -> Result<WorldSnapshot, SnapshotError> {
+    let registry = registry(data);
+    let mut entities: Vec<Entity> = data
+        .entities()
+        .into_iter()
+        .filter(|entity| !registry.excluded.iter().any(|excluded| excluded(data, *entity)))
+        .collect();
+    entities.sort_by_key(|entity| entity.to_bits());
+
+    let mut snapshot = WorldSnapshot::default();
+    for entity in entities {
+        let mut components = BTreeMap::new();
+        for registration in registry.components.iter() {
+            if let Some(value) = (registration.serialize)(data, entity) {
+                let value = value.map_err(|e| serialization_error(&registration.name, e))?;
+                components.insert(registration.name.clone(), value);
+            }
+        }
+        snapshot.entities.push(EntitySnapshot { id: entity.to_bits().get(), components });
+    }
+    for registration in registry.resources.iter() {
+        if let Some(value) = (registration.serialize)(data) {
+            let value = value.map_err(|e| serialization_error(&registration.name, e))?;
+            snapshot.resources.insert(registration.name.clone(), value);
+        }
+    }
+    Ok(snapshot)
+}
+
+/// Replaces the entities of the world and the registered resources with the ones of `snapshot`.
+/// Nothing is modified if the snapshot can't be deserialized.
+pub(crate) fn restore_snapshot(data: &mut GameData, snapshot: &WorldSnapshot) -> Result<EntityMap, SnapshotError> {
+    let registry = registry(data);
+    let mut pending_entities = Vec::with_capacity(snapshot.entities.len());
+    for entity_snapshot in snapshot.entities.iter() {
+        let entity = Entity::from_bits(entity_snapshot.id)
+            .ok_or_else(|| SnapshotError::InvalidFormat(format!("invalid entity id {}", entity_snapshot.id)))?;
+        let mut components = Vec::with_capacity(entity_snapshot.components.len());
+        for (name, value) in entity_snapshot.components.iter() {
+            let registration = registry
+                .components
+                .iter()
+                .find(|registration| &registration.name == name)
+                .ok_or_else(|| SnapshotError::Unregistered(name.clone()))?;
+            components.push((registration.deserialize)(value.clone()).map_err(|e| serialization_error(name, e))?);
+        }
+        pending_entities.push((entity, components));
+    }
+    let mut pending_resources = Vec::with_capacity(snapshot.resources.len());
+    for (name, value) in snapshot.resources.iter() {
+        let registration = registry
+            .resources
+            .iter()
+            .find(|registration| &registration.name == name)
+            .ok_or_else(|| SnapshotError::Unregistered(name.clone()))?;
+        pending_resources.push((registration.deserialize)(value.clone()).map_err(|e| serialization_error(name, e))?);
+    }
+
+    data.clear();
+    let mut map = EntityMap::default();
+    for (entity, _) in pending_entities.iter() {
+        map.entities.insert(*entity, data.reserve_entity());
+    }
+    for (entity, components) in pending_entities {
+        let mut builder = EntityBuilder::new();
+        components.into_iter().for_each(|component| component(&map, &mut builder));
+        let _r = data.add_components(map.entities[&entity], builder.build());
+    }
+    pending_resources.into_iter().for_each(|resource| resource(data));
+    Ok(map)
+}
+
+/// (De)serialization of an [`Entity`] as its id, to use with `#[serde(with = "...")]`
+pub(crate) mod serde_entity {
+    use hecs::Entity;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(entity: &Entity, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(entity.to_bits().get())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Entity, D::Error> {
+        let bits = u64::deserialize(deserializer)?;
+        Entity::from_bits(bits).ok_or_else(|| D::Error::custom(format!("invalid entity id {}", bits)))
+    }
+}
+
+/// (De)serialization of a list of [`Entity`] as their ids, to use with `#[serde(with = "...")]`
+pub(crate) mod serde_entities {
+    use hecs::Entity;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(entities: &[Entity], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(entities.iter().map(|entity| entity.to_bits().get()))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Entity>, D::Error> {
+        Vec::<u64>::deserialize(deserializer)?
+            .into_iter()
+            .map(|bits| Entity::from_bits(bits).ok_or_else(|| D::Error::custom(format!("invalid entity id {}", bits))))
+            .collect()
+    }
+}
+
+/// Binary encoding of a json value : a tag byte followed by the little endian content
+mod binary {
+    use std::convert::TryInto;
+
+    use super::*;
+
+    const NULL: u8 = 0;
+    const FALSE: u8 = 1;
+    const TRUE: u8 = 2;
+    const UNSIGNED: u8 = 3;
+    const SIGNED: u8 = 4;
+    const FLOAT: u8 = 5;
+    const STRING: u8 = 6;
+    const ARRAY: u8 = 7;
+    const OBJECT: u8 = 8;
+
+    /// Maximum number of nested arrays and objects, like `serde_json`, so that reading can't overflow the stack
+    const MAX_DEPTH: usize = 128;
+    const TRUNCATED: &str = "truncated binary snapshot";
+
+    pub(super) fn write(value: &Value, bytes: &mut Vec<u8>) {
+        match value {
+            Value::Null => bytes.push(NULL),
+            Value::Bool(false) => bytes.push(FALSE),
+            Value::Bool(true) => bytes.push(TRUE),
+            Value::Number(number) => {
+                if let Some(n) = number.as_u64() {
+                    bytes.push(UNSIGNED);
+                    bytes.extend_from_slice(&n.to_le_bytes());
+                } else if let Some(n) = number.as_i64() {
+                    bytes.push(SIGNED);
+                    bytes.extend_from_slice(&n.to_le_bytes());
+                } else {
+                    bytes.push(FLOAT);
+                    bytes.extend_from_slice(&number.as_f64().unwrap_or_default().to_le_bytes());
+                }
+            }
+            Value::String(string) => {
+                bytes.push(STRING);
+                write_str(string, bytes);
+            }
+            Value::Array(values) => {
+                bytes.push(ARRAY);
+                bytes.extend_from_slice(&(values.len() as u32).to_le_bytes());
+                values.iter().for_each(|value| write(value, bytes));
+            }
+            Value::Object(map) => {
+                bytes.push(OBJECT);
+                bytes.extend_from_slice(&(map.len() as u32).to_le_bytes());
+                map.iter().for_each(|(key, value)| {
+                    write_str(key, bytes);
+                    write(value, bytes);
+                });
+            }
+        }
+    }
+
+    fn write_str(string: &str, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&(string.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(string.as_bytes());
+    }
+
+    pub(super) struct Reader<'a> {
+        pub(super) bytes: &'a [u8],
+        /// Number of arrays and objects containing the value being read
+        pub(super) depth: usize,
+    }
+
+    impl Reader<'_> {
+        /// Reads the next value, or the reason why the bytes are not a valid encoding
+        pub(super) fn read(&mut self) -> Result<Value, &'static str> {
+            let value = match self.take(1)?[0] {
+                NULL => Value::Null,
+                FALSE => Value::Bool(false),
+                TRUE => Value::Bool(true),
+                UNSIGNED => Value::Number(u64::from_le_bytes(self.take_8()?).into()),
+                SIGNED => Value::Number(i64::from_le_bytes(self.take_8()?).into()),
+                FLOAT => Value::Number(Number::from_f64(f64::from_le_bytes(self.take_8()?)).ok_or("invalid float")?),
+                STRING => Value::String(self.read_str()?),
+                ARRAY => {
+                    let len = self.enter()?;
+                    let values = (0..len).map(|_| self.read()).collect::<Result<Vec<_>, _>>()?;
+                    self.depth -= 1;
+                    Value::Array(values)
+                }
+                OBJECT => {
+                    let len = self.enter()?;
+                    let mut map = Map::new();
+                    for _ in 0..len {
+                        let key = self.read_str()?;
+                        map.insert(key, self.read()?);
+                    }
+                    self.depth -= 1;
+                    Value::Object(map)
+                }
+                _ => return Err("unknown value tag"),
+            };
+            Ok(value)
+        }
+
+        /// Starts reading a nested array or object, returning its length
+        fn enter(&mut self) -> Result<usize, &'static str> {
+            if self.depth >= MAX_DEPTH {
+                return Err("values nested too deeply");
+            }
+            self.depth += 1;
+            self.read_len()
+        }
+
+        fn read_len(&mut self) -> Result<usize, &'static str> {
+            Ok(u32::from_le_bytes(self.take(4)?.try_into().map_err(|_| TRUNCATED)?) as usize)
+        }
+
+        fn read_str(&mut self) -> Result<String, &'static str> {
+            let len = self.read_len()?;
+            String::from_utf8(self.take(len)?.to_vec()).map_err(|_| "invalid utf-8 string")
+        }
+
+        fn take_8(&mut self) -> Result<[u8; 8], &'static str> {
+            self.take(8)?.try_into().map_err(|_| TRUNCATED)
+        }
+
+        fn take(&mut self, len: usize) -> Result<&[u8], &'static str> {
+            if self.bytes.len() < len {
+                return Err(TRUNCATED);
+            }
+            let (taken, rest) = self.bytes.split_at(len);
+            self.bytes = rest;
+            Ok(taken)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::core::components::maths::collider::{ColliderMask, ColliderType};
+    use crate::core::resources::time::TimerType;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Score(u32);
+
+    #[derive(Serialize, Deserialize)]
+    struct Target(#[serde(with = "serde_entity")] Entity);
+
+    impl MapEntities for Target {
+        fn map_entities(&mut self, map: &EntityMap) -> bool {
+            map.get(self.0).map(|target| self.0 = target).is_some()
+        }
+    }
+
+    fn data() -> GameData {
+        let mut data = GameData::default();
+        let mut registry = SnapshotRegistry::default();
+        registry.register_component::<Score>("score");
+        registry.register_component_with_entities::<Target>("target");
+        data.insert_resource(registry);
+        data.insert_resource(GameState::default());
+        data.insert_resource(Timers::default());
+        data
+    }
+
+    #[test]
+    fn snapshot_restore_test() {
+        let mut data = data();
+        // Shifts the ids of the original entities compared to the restored ones
+        let removed = data.push((1u8,));
+        let _r = data.remove(removed);
+        let hud = data.push((Name::new("hud"), Transform::from_xy(1., 2.)));
+        let score = data.push((Name::new("score"), Parent(hud), Score(42), 3u8));
+        let _r = data.add_components(hud, (Children(vec![score]), Target(score)));
+        data.game_state_mut().set_bool("paused", true);
+        let _r = data.timers().add_timer("spawn", TimerType::Cyclic, 2.);
+
+        let snapshot = data.snapshot().unwrap();
+        let json = snapshot.to_json().unwrap();
+        let binary = snapshot.to_binary().unwrap();
+        data.clear();
+        data.game_state_mut().set_bool("paused", false);
+
+        for snapshot in [WorldSnapshot::from_json(&json).unwrap(), WorldSnapshot::from_binary(&binary).unwrap()] {
+            let map = data.restore(&snapshot).unwrap();
+            assert_eq!(2, data.entities().len());
+            let new_score = data.find_by_path("hud/score").unwrap();
+            let new_hud = data.find_by_name("hud").unwrap();
+            assert_eq!(Some(new_score), map.get(score));
+            assert_eq!(Score(42), *data.entry::<&Score>(new_score).unwrap().get().unwrap());
+            // Not registered
+            assert!(data.entry::<&u8>(new_score).unwrap().get().is_none());
+            assert_eq!(vec![new_score], data.entry::<&Children>(new_hud).unwrap().get().unwrap().0);
+            assert_eq!(new_score, data.entry::<&Target>(new_hud).unwrap().get().unwrap().0);
+            assert_eq!(2., data.entry::<&Transform>(new_hud).unwrap().get().unwrap().translation().y());
+            assert!(data.game_state().get_bool("paused"));
+            assert!(data.timers().exists("spawn"));
+        }
+    }
+
+    #[test]
+    fn references_to_excluded_entities_are_dropped_test() {
+        let mut data = data();
+        let collider = data.push((
+            Transform::default(),
+            Collider::new(ColliderMask::None, vec![], ColliderType::SquareCollider(10)),
+        ));
+        let debug = data.push((ColliderDebug, Parent(collider)));
+        let _r = data.add_components(collider, (Children(vec![debug]),));
+        data.push((Score(1), Parent(debug)));
+
+        let snapshot = data.snapshot().unwrap();
+        let map = data.restore(&snapshot).unwrap();
+
+        assert_eq!(2, data.entities().len());
+        let new_collider = map.get(collider).unwrap();
+        let score = data.query::<&Score>().iter().map(|(entity, _)| entity).next().unwrap();
+        assert!(map.get(debug).is_none());
+        assert!(data.entry::<&Children>(new_collider).unwrap().get().is_none());
+        assert!(data.entry::<&Parent>(score).unwrap().get().is_none());
+
+        data.remove_recursive(new_collider).unwrap();
+        assert_eq!(HashSet::from([score]), data.entities());
+    }
+
+    #[test]
+    fn invalid_snapshot_is_not_restored_test() {
+        let mut data = data();
+        data.push((Score(1),));
+        let snapshot = data.snapshot().unwrap();
+
+        let mut other = GameData::default();
+        let entity = other.push((Score(2),));
+        assert!(matches!(other.restore(&snapshot), Err(SnapshotError::Unregistered(name)) if name == "score"));
+        assert!(other.contains(entity));
+
+        assert!(WorldSnapshot::from_binary(&snapshot.to_binary().unwrap()[..10]).is_err());
+        let mut nested = BINARY_MAGIC.to_vec();
+        nested.push(BINARY_VERSION);
+        (0..100_000).for_each(|_| nested.extend_from_slice(&[7, 1, 0, 0, 0]));
+        assert!(matches!(WorldSnapshot::from_binary(&nested), Err(SnapshotError::InvalidFormat(_))));
+        assert!(WorldSnapshot::from_json("{").is_err());
+    }
+}
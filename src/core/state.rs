@@ -1,36 +1,350 @@
 use std::collections::HashMap;
+use std::fmt::{Debug, Display, Formatter};
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::core::world::GameData;
+use crate::utils::file::{read_file, write_file_atomically};
+use crate::utils::ScionError;
+
+/// Name of the event topic in which the keys of the [`GameState`] values changed during a frame are published,
+/// at the end of the frame
+pub const GAME_STATE_TOPIC: &str = "GameState";
+
+/// A value stored in the [`GameState`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StateValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    List(Vec<StateValue>),
+    /// A user defined serializable value, see [`GameState::set_data`]
+    Data(serde_json::Value),
+}
+
+impl Display for StateValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateValue::Bool(value) => write!(f, "{}", value),
+            StateValue::Int(value) => write!(f, "{}", value),
+            StateValue::Float(value) => write!(f, "{}", value),
+            StateValue::Text(value) => write!(f, "{}", value),
+            StateValue::List(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            StateValue::Data(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<bool> for StateValue {
+    fn from(value: bool) -> Self {
+        StateValue::Bool(value)
+    }
+}
+
+impl From<i64> for StateValue {
+    fn from(value: i64) -> Self {
+        StateValue::Int(value)
+    }
+}
+
+impl From<i32> for StateValue {
+    fn from(value: i32) -> Self {
+        StateValue::Int(value as i64)
+    }
+}
+
+impl From<usize> for StateValue {
+    fn from(value: usize) -> Self {
+        StateValue::Int(value as i64)
+    }
+}
+
+impl From<f64> for StateValue {
+    fn from(value: f64) -> Self {
+        StateValue::Float(value)
+    }
+}
+
+impl From<f32> for StateValue {
+    fn from(value: f32) -> Self {
+        StateValue::Float(value as f64)
+    }
+}
+
+impl From<&str> for StateValue {
+    fn from(value: &str) -> Self {
+        StateValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for StateValue {
+    fn from(value: String) -> Self {
+        StateValue::Text(value)
+    }
+}
+
+impl<T: Into<StateValue>> From<Vec<T>> for StateValue {
+    fn from(values: Vec<T>) -> Self {
+        StateValue::List(values.into_iter().map(|value| value.into()).collect())
+    }
+}
+
+type ChangeCallback = Box<dyn FnMut(&StateValue) + Send + Sync>;
 
 /// `GameState` is a convenience Resource created to keep track of
 /// diverse thing internally. It's also the resource used to create
 /// pausable systems.
-#[derive(Debug, Default)]
+///
+/// Each value is identified by a key. Each time a value changes, its key is published in the [`GAME_STATE_TOPIC`]
+/// event topic at the end of the frame and the callbacks registered with [`GameState::on_change`] are executed.
+#[derive(Default, Serialize, Deserialize)]
 pub struct GameState {
-    flags: HashMap<String, bool>,
-    text: HashMap<String, String>,
+    values: HashMap<String, StateValue>,
+    /// Incremented at each change
+    #[serde(skip)]
+    version: u64,
+    #[serde(skip)]
+    changed_at: HashMap<String, u64>,
+    /// Keys changed since the last time they were published
+    #[serde(skip)]
+    pending_changes: Vec<String>,
+    #[serde(skip)]
+    callbacks: HashMap<String, Vec<ChangeCallback>>,
+}
+
+impl Debug for GameState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GameState").field("values", &self.values).field("version", &self.version).finish()
+    }
 }
 
 impl GameState {
     pub fn get_bool(&self, key: &str) -> bool {
-        if self.flags.contains_key(key) {
-            return *self.flags.get(key).unwrap();
-        } else {
-            false
-        }
+        matches!(self.values.get(key), Some(StateValue::Bool(true)))
     }
 
     pub fn set_bool(&mut self, key: &str, val: bool) {
-        self.flags.insert(key.to_string(), val);
+        self.set(key, val);
     }
 
     pub fn get_text(&self, key: &str) -> Option<String> {
-        if self.text.contains_key(key) {
-            return Some(self.text.get(key).unwrap().to_string());
-        } else {
-            None
+        match self.values.get(key) {
+            Some(StateValue::Text(text)) => Some(text.to_string()),
+            _ => None,
         }
     }
 
     pub fn set_text(&mut self, key: &str, val: &str) {
-        self.text.insert(key.to_string(), val.to_string());
+        self.set(key, val);
+    }
+
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        match self.values.get(key) {
+            Some(StateValue::Int(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn get_float(&self, key: &str) -> Option<f64> {
+        match self.values.get(key) {
+            Some(StateValue::Float(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn get_list(&self, key: &str) -> Option<&Vec<StateValue>> {
+        match self.values.get(key) {
+            Some(StateValue::List(values)) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Retrieves the value of `key`, whatever its type
+    pub fn get(&self, key: &str) -> Option<&StateValue> {
+        self.values.get(key)
+    }
+
+    /// Sets the value of `key`. The change is notified only if the value is different from the current one.
+    pub fn set(&mut self, key: &str, value: impl Into<StateValue>) {
+        let value = value.into();
+        if self.values.get(key) == Some(&value) {
+            return;
+        }
+        if let Some(callbacks) = self.callbacks.get_mut(key) {
+            callbacks.iter_mut().for_each(|callback| callback(&value));
+        }
+        self.values.insert(key.to_string(), value);
+        self.mark_changed(key);
+    }
+
+    /// Adds `delta` to the integer value of `key`, considered as 0 when missing or of another type
+    pub fn add_int(&mut self, key: &str, delta: i64) -> i64 {
+        let value = self.get_int(key).unwrap_or_default() + delta;
+        self.set(key, value);
+        value
+    }
+
+    /// Stores a user defined serializable value under `key`
+    pub fn set_data<T: Serialize>(&mut self, key: &str, data: &T) -> Result<(), ScionError> {
+        let value = serde_json::to_value(data).map_err(|e| ScionError::new(&e.to_string()))?;
+        self.set(key, StateValue::Data(value));
+        Ok(())
+    }
+
+    /// Retrieves a user defined value stored with [`GameState::set_data`], `None` if it is missing or of another type
+    pub fn get_data<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        match self.values.get(key) {
+            Some(StateValue::Data(value)) => serde_json::from_value(value.clone()).ok(),
+            _ => None,
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<StateValue> {
+        let removed = self.values.remove(key);
+        if removed.is_some() {
+            self.mark_changed(key);
+        }
+        removed
+    }
+
+    /// Registers a callback executed with the new value each time the value of `key` changes
+    pub fn on_change<F: FnMut(&StateValue) + Send + Sync + 'static>(&mut self, key: &str, callback: F) {
+        self.callbacks.entry(key.to_string()).or_default().push(Box::new(callback));
+    }
+
+    /// Current change version, to give back to [`GameState::changed_since`]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Whether the value of `key` changed after `version`
+    pub fn changed_since(&self, key: &str, version: u64) -> bool {
+        self.changed_at.get(key).is_some_and(|changed_at| *changed_at > version)
+    }
+
+    /// Writes the values to the json file at `path`
+    pub fn save(&self, path: &Path) -> Result<(), ScionError> {
+        let bytes = serde_json::to_vec(&self.values).map_err(|e| ScionError::new(&e.to_string()))?;
+        write_file_atomically(path, &bytes)
+            .map_err(|e| ScionError::new(&format!("Impossible to save the game state to {:?}: {}", path, e)))
+    }
+
+    /// Replaces the values with the ones of the json file at `path`, written by [`GameState::save`].
+    /// Changed values are notified.
+    pub fn load(&mut self, path: &Path) -> Result<(), ScionError> {
+        let bytes = read_file(path)
+            .map_err(|e| ScionError::new(&format!("Impossible to load the game state from {:?}: {:?}", path, e)))?;
+        let values: HashMap<String, StateValue> =
+            serde_json::from_slice(&bytes).map_err(|e| ScionError::new(&e.to_string()))?;
+        let removed: Vec<String> = self.values.keys().filter(|key| !values.contains_key(*key)).cloned().collect();
+        removed.iter().for_each(|key| {
+            self.remove(key);
+        });
+        values.into_iter().for_each(|(key, value)| self.set(&key, value));
+        Ok(())
+    }
+
+    /// Keys changed since the previous call, to be published
+    pub(crate) fn take_changes(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending_changes)
+    }
+
+    fn mark_changed(&mut self, key: &str) {
+        self.version += 1;
+        self.changed_at.insert(key.to_string(), self.version);
+        if !self.pending_changes.iter().any(|pending| pending == key) {
+            self.pending_changes.push(key.to_string());
+        }
+    }
+}
+
+/// Publishes the keys of the values changed during the frame in the [`GAME_STATE_TOPIC`] topic, one message per key
+pub(crate) fn publish_game_state_changes(data: &GameData) {
+    let changes = match data.get_resource_mut::<GameState>() {
+        Some(mut state) => state.take_changes(),
+        None => return,
+    };
+    if changes.is_empty() {
+        return;
+    }
+    let mut events = data.events();
+    for key in changes {
+        if let Err(e) = events.publish(GAME_STATE_TOPIC, key) {
+            log::error!("Error while publishing the game state changes: {:?}", e);
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Progress {
+        level: usize,
+        unlocked: Vec<String>,
+    }
+
+    #[test]
+    fn typed_values_test() {
+        let mut state = GameState::default();
+        state.set("lives", 3);
+        state.set("speed", 1.5);
+        state.set("levels", vec!["intro", "forest"]);
+        state.set_bool("paused", true);
+        let progress = Progress { level: 2, unlocked: vec!["sword".to_string()] };
+        state.set_data("progress", &progress).unwrap();
+
+        assert_eq!(Some(2), Some(state.add_int("lives", -1)));
+        assert_eq!(Some(1.5), state.get_float("speed"));
+        assert_eq!("[intro, forest]", state.get("levels").unwrap().to_string());
+        assert!(state.get_bool("paused"));
+        assert!(state.get_text("paused").is_none());
+        assert_eq!(Some(progress), state.get_data::<Progress>("progress"));
+
+        let path = std::env::temp_dir().join(format!("scion_game_state_{}.json", std::process::id()));
+        state.save(&path).unwrap();
+        let mut loaded = GameState::default();
+        loaded.set("obsolete", true);
+        loaded.load(&path).unwrap();
+        assert_eq!(Some(2), loaded.get_int("lives"));
+        assert!(loaded.get("obsolete").is_none());
+        let _r = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn change_notifications_test() {
+        let mut state = GameState::default();
+        let notified = Arc::new(Mutex::new(Vec::new()));
+        let callback_notified = notified.clone();
+        state.on_change("score", move |value| callback_notified.lock().unwrap().push(value.to_string()));
+
+        state.set("score", 10);
+        let version = state.version();
+        state.set("score", 10);
+        state.set("lives", 3);
+        assert!(!state.changed_since("score", version));
+        assert!(state.changed_since("lives", version));
+        state.set("score", 20);
+        assert!(state.changed_since("score", version));
+
+        assert_eq!(vec!["10", "20"], *notified.lock().unwrap());
+        assert_eq!(vec!["score", "lives"], state.take_changes());
+        assert!(state.take_changes().is_empty());
     }
 }
@@ -0,0 +1,400 @@
+//! Typed parameters of the systems, fetched from the `GameData` before each execution.
+//!
+//! A typed system is a function whose parameters all implement [`SystemParam`], for example
+//! `fn move_system(time: Res<Time>, mut query: Query<(&mut Transform, &Velocity)>)`.
+//! The resources and components borrowed by the parameters are checked when the system is registered,
+//! so that two parameters of the same system can't borrow the same data mutably.
+
+use std::any::{type_name, TypeId};
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use atomic_refcell::{AtomicRef, AtomicRefMut};
+use hecs::{Component, QueryBorrow, QueryIter};
+
+use crate::core::change_detection::SystemTicks;
+use crate::core::scheduler::System;
+use crate::core::world::{GameData, Resource, World};
+
+/// Errors that can happen while registering a typed system
+#[derive(Debug, PartialEq)]
+pub enum SystemParamError {
+    /// Two parameters of `system` borrow `type_name`, and at least one of them mutably
+    BorrowConflict { system: &'static str, type_name: &'static str },
+}
+
+impl Display for SystemParamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SystemParamError::BorrowConflict { system, type_name } => {
+                write!(f, "System {} borrows {} mutably more than once, or both mutably and immutably", system, type_name)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum AccessTarget {
+    Resource,
+    Component,
+}
+
+/// `SystemAccess` lists the resources and components borrowed by the parameters of a system
+#[derive(Default)]
+pub struct SystemAccess {
+    system: &'static str,
+    accesses: Vec<(AccessTarget, TypeId, &'static str, bool)>,
+}
+
+impl SystemAccess {
+    fn new(system: &'static str) -> Self {
+        Self { system, accesses: vec![] }
+    }
+
+    /// Declares a borrow of the resource `T`
+    pub fn add_resource<T: Resource>(&mut self, mutable: bool) {
+        self.accesses.push((AccessTarget::Resource, TypeId::of::<T>(), type_name::<T>(), mutable));
+    }
+
+    /// Declares a borrow of the component `T`
+    pub fn add_component<T: Component>(&mut self, mutable: bool) {
+        self.accesses.push((AccessTarget::Component, TypeId::of::<T>(), type_name::<T>(), mutable));
+    }
+
+    /// Whether a system with `other` access can't be executed at the same time as this one
+    pub(crate) fn conflicts_with(&self, other: &SystemAccess) -> bool {
+        self.accesses.iter().any(|(target, type_id, _, mutable)| {
+            other.accesses.iter().any(|(other_target, other_id, _, other_mutable)| {
+                other_target == target && other_id == type_id && (*mutable || *other_mutable)
+            })
+        })
+    }
+
+    pub(crate) fn extend(&mut self, other: &SystemAccess) {
+        self.accesses.extend(other.accesses.iter().copied());
+    }
+
+    fn check(&self) -> Result<(), SystemParamError> {
+        for (index, (target, type_id, name, mutable)) in self.accesses.iter().enumerate() {
+            let conflict = self.accesses[index + 1..]
+                .iter()
+                .any(|(other_target, other_id, _, other_mutable)| {
+                    other_target == target && other_id == type_id && (*mutable || *other_mutable)
+                });
+            if conflict {
+                return Err(SystemParamError::BorrowConflict { system: self.system, type_name: name });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Trait implemented by the types that can be used as parameters of a typed system
+pub trait SystemParam {
+    /// Type given to the system, which is the parameter itself with the lifetime of the current execution
+    type Item<'a>;
+
+    /// Declares the resources and components borrowed by this parameter
+    fn access(access: &mut SystemAccess);
+
+    /// Retrieves the parameter from the game data. `ticks` tells when the system was last executed,
+    /// see [`crate::core::change_detection`].
+    fn fetch(data: &GameData, ticks: SystemTicks) -> Self::Item<'_>;
+}
+
+type SystemParamItem<'a, P> = <P as SystemParam>::Item<'a>;
+
+/// Immutable access to the resource `T`, which must be thread safe as typed systems can be executed in parallel. The system panics if the resource is missing, use `Option<Res<T>>` otherwise.
+pub struct Res<'a, T: Resource + Send + Sync> {
+    inner: AtomicRef<'a, T>,
+}
+
+impl<T: Resource + Send + Sync> Deref for Res<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+/// Mutable access to the resource `T`, which must be thread safe as typed systems can be executed in parallel. The system panics if the resource is missing, use `Option<ResMut<T>>` otherwise.
+pub struct ResMut<'a, T: Resource + Send + Sync> {
+    inner: AtomicRefMut<'a, T>,
+}
+
+impl<T: Resource + Send + Sync> Deref for ResMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Resource + Send + Sync> DerefMut for ResMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+}
+
+impl<T: Resource + Send + Sync> SystemParam for Res<'_, T> {
+    type Item<'a> = Res<'a, T>;
+
+    fn access(access: &mut SystemAccess) {
+        access.add_resource::<T>(false);
+    }
+
+    fn fetch(data: &GameData, _ticks: SystemTicks) -> Res<'_, T> {
+        let inner = data.get_resource::<T>().unwrap_or_else(|| panic!("Missing resource {}", type_name::<T>()));
+        Res { inner }
+    }
+}
+
+impl<T: Resource + Send + Sync> SystemParam for ResMut<'_, T> {
+    type Item<'a> = ResMut<'a, T>;
+
+    fn access(access: &mut SystemAccess) {
+        access.add_resource::<T>(true);
+    }
+
+    fn fetch(data: &GameData, _ticks: SystemTicks) -> ResMut<'_, T> {
+        let inner = data.get_resource_mut::<T>().unwrap_or_else(|| panic!("Missing resource {}", type_name::<T>()));
+        ResMut { inner }
+    }
+}
+
+impl<T: Resource + Send + Sync> SystemParam for Option<Res<'_, T>> {
+    type Item<'a> = Option<Res<'a, T>>;
+
+    fn access(access: &mut SystemAccess) {
+        access.add_resource::<T>(false);
+    }
+
+    fn fetch(data: &GameData, _ticks: SystemTicks) -> Option<Res<'_, T>> {
+        data.get_resource::<T>().map(|inner| Res { inner })
+    }
+}
+
+impl<T: Resource + Send + Sync> SystemParam for Option<ResMut<'_, T>> {
+    type Item<'a> = Option<ResMut<'a, T>>;
+
+    fn access(access: &mut SystemAccess) {
+        access.add_resource::<T>(true);
+    }
+
+    fn fetch(data: &GameData, _ticks: SystemTicks) -> Option<ResMut<'_, T>> {
+        data.get_resource_mut::<T>().map(|inner| ResMut { inner })
+    }
+}
+
+/// Trait implemented by the hecs queries usable in a [`Query`] parameter, declaring the components they borrow
+pub trait QueryAccess: hecs::Query {
+    fn access(access: &mut SystemAccess);
+}
+
+impl<T: Component> QueryAccess for &T {
+    fn access(access: &mut SystemAccess) {
+        access.add_component::<T>(false);
+    }
+}
+
+impl<T: Component> QueryAccess for &mut T {
+    fn access(access: &mut SystemAccess) {
+        access.add_component::<T>(true);
+    }
+}
+
+impl<Q: QueryAccess> QueryAccess for Option<Q> {
+    fn access(access: &mut SystemAccess) {
+        Q::access(access);
+    }
+}
+
+macro_rules! impl_query_access {
+    ($($q:ident),*) => {
+        impl<$($q: QueryAccess),*> QueryAccess for ($($q,)*) {
+            fn access(access: &mut SystemAccess) {
+                $($q::access(access);)*
+            }
+        }
+    };
+}
+
+impl_query_access!(A);
+impl_query_access!(A, B);
+impl_query_access!(A, B, C);
+impl_query_access!(A, B, C, D);
+impl_query_access!(A, B, C, D, E);
+impl_query_access!(A, B, C, D, E, F);
+impl_query_access!(A, B, C, D, E, F, G);
+impl_query_access!(A, B, C, D, E, F, G, H);
+
+/// Query over the entities of the world having the components of `Q`
+pub struct Query<'a, Q: QueryAccess>(QueryBorrow<'a, Q>);
+
+impl<Q: QueryAccess> Query<'_, Q> {
+    /// Iterates over the entities matching the query
+    pub fn iter(&mut self) -> QueryIter<'_, Q> {
+        self.0.iter()
+    }
+}
+
+impl<Q: QueryAccess> SystemParam for Query<'_, Q> {
+    type Item<'a> = Query<'a, Q>;
+
+    fn access(access: &mut SystemAccess) {
+        Q::access(access);
+    }
+
+    fn fetch(data: &GameData, _ticks: SystemTicks) -> Query<'_, Q> {
+        Query(data.query::<Q>())
+    }
+}
+
+/// Trait implemented by the functions whose parameters are all [`SystemParam`]
+pub trait SystemParamFunction<P>: Send + 'static {
+    fn access(access: &mut SystemAccess);
+
+    fn run(&mut self, data: &GameData, ticks: SystemTicks);
+}
+
+macro_rules! impl_system_param_function {
+    ($($param:ident),*) => {
+        #[allow(non_snake_case)]
+        impl<Func, $($param: SystemParam),*> SystemParamFunction<($($param,)*)> for Func
+        where
+            Func: Send + 'static,
+            for<'a> &'a mut Func: FnMut($($param),*) + FnMut($(SystemParamItem<'_, $param>),*),
+        {
+            fn access(_access: &mut SystemAccess) {
+                $($param::access(_access);)*
+            }
+
+            fn run(&mut self, _data: &GameData, _ticks: SystemTicks) {
+                // Helps the compiler to pick the `FnMut` implementation taking the items
+                #[allow(clippy::too_many_arguments)]
+                fn call_inner<$($param),*>(mut f: impl FnMut($($param),*), $($param: $param),*) {
+                    f($($param),*)
+                }
+                $(let $param = $param::fetch(_data, _ticks);)*
+                call_inner(self, $($param),*)
+            }
+        }
+    };
+}
+
+impl_system_param_function!();
+impl_system_param_function!(A);
+impl_system_param_function!(A, B);
+impl_system_param_function!(A, B, C);
+impl_system_param_function!(A, B, C, D);
+impl_system_param_function!(A, B, C, D, E);
+impl_system_param_function!(A, B, C, D, E, F);
+impl_system_param_function!(A, B, C, D, E, F, G);
+impl_system_param_function!(A, B, C, D, E, F, G, H);
+
+/// `TypedSystem` wraps a function taking [`SystemParam`] parameters into a [`System`].
+pub struct TypedSystem<F, P> {
+    function: F,
+    access: SystemAccess,
+    /// Change tick of the previous execution
+    last_run: u64,
+    _params: PhantomData<fn() -> P>,
+}
+
+/// Systems that only use the data declared in their access, and so can be executed in parallel
+pub(crate) trait ParallelSystem: Send {
+    fn access(&self) -> &SystemAccess;
+
+    fn run_shared(&mut self, data: &GameData);
+}
+
+impl<F: SystemParamFunction<P>, P: 'static> TypedSystem<F, P> {
+    /// Creates the system, checking that its parameters don't borrow the same data in conflicting ways
+    pub fn new(function: F) -> Result<Self, SystemParamError> {
+        let mut access = SystemAccess::new(type_name::<F>());
+        F::access(&mut access);
+        access.check()?;
+        Ok(Self { function, access, last_run: 0, _params: PhantomData })
+    }
+
+    fn execute(&mut self, data: &GameData) {
+        let this_run = data.changes().begin_system();
+        self.function.run(data, SystemTicks { last_run: self.last_run, this_run });
+        data.changes().end_system();
+        self.last_run = this_run;
+    }
+}
+
+impl<F: SystemParamFunction<P>, P: 'static> System for TypedSystem<F, P> {
+    fn run(&mut self, data: &mut GameData) {
+        self.execute(data);
+    }
+}
+
+impl<F: SystemParamFunction<P>, P: 'static> ParallelSystem for TypedSystem<F, P> {
+    fn access(&self) -> &SystemAccess {
+        &self.access
+    }
+
+    fn run_shared(&mut self, data: &GameData) {
+        self.execute(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::resources::time::Time;
+
+    use super::*;
+
+    struct Speed(f32);
+
+    struct Position(f32);
+
+    struct Count(usize);
+
+    fn move_system(time: Res<Time>, mut count: ResMut<Count>, mut query: Query<(&Speed, &mut Position)>) {
+        for (_, (speed, position)) in query.iter() {
+            position.0 += speed.0 * time.delta_duration().as_secs_f32();
+            count.0 += 1;
+        }
+    }
+
+    fn conflicting_resources_system(_a: Res<Count>, _b: ResMut<Count>) {}
+
+    fn conflicting_queries_system(_a: Query<&Position>, _b: Query<(&Speed, &mut Position)>) {}
+
+    fn optional_system(missing: Option<Res<Speed>>, mut count: ResMut<Count>) {
+        if missing.is_none() {
+            count.0 += 10;
+        }
+    }
+
+    #[test]
+    fn typed_system_fetches_its_params_test() {
+        let mut data = GameData::default();
+        data.insert_resource(Time::default());
+        data.insert_resource(Count(0));
+        let entity = data.push((Speed(2.), Position(1.)));
+
+        let mut system = TypedSystem::new(move_system).unwrap();
+        system.run(&mut data);
+        TypedSystem::new(optional_system).unwrap().run(&mut data);
+
+        assert_eq!(11, data.get_resource::<Count>().unwrap().0);
+        assert_eq!(1., data.entry::<&Position>(entity).unwrap().get().unwrap().0);
+    }
+
+    #[test]
+    fn borrow_conflicts_are_detected_test() {
+        assert!(matches!(
+            TypedSystem::new(conflicting_resources_system),
+            Err(SystemParamError::BorrowConflict { type_name, .. }) if type_name.ends_with("Count")
+        ));
+        assert!(matches!(
+            TypedSystem::new(conflicting_queries_system),
+            Err(SystemParamError::BorrowConflict { type_name, .. }) if type_name.ends_with("Position")
+        ));
+    }
+}
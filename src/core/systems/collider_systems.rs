@@ -4,6 +4,7 @@ use hecs::{Component, Entity};
 use crate::core::components::maths::collider::{Collider, ColliderDebug, ColliderMask, Collision};
 use crate::core::components::maths::hierarchy::Parent;
 use crate::core::components::maths::transform::Transform;
+use crate::core::components::name::Name;
 
 use crate::graphics::components::{color::Color, material::Material, shapes::polygon::Polygon};
 use crate::core::resources::global_storage::GlobalStorage;
@@ -77,10 +78,10 @@ pub(crate) fn compute_collisions_system(data: &mut GameData) {
 pub(crate) fn debug_colliders_system(data: &mut GameData) {
     let global_debug_activated = handle_global_debug_colliders(data);
     let mut collider_debug = fetch_collider_debug_entities(data);
-    let mut debug_lines_to_add = Vec::new();
-    let mut debug_lines_to_remove = Vec::new();
+    let (world, resources) = data.split();
+    let mut commands = resources.commands();
     for (entity, (_, collider))
-    in data.query_mut::<(&Transform, &mut Collider)>() {
+    in world.query_mut::<(&Transform, &mut Collider)>() {
         if (collider.debug_lines() || global_debug_activated) && !collider_debug.0.contains(&entity) {
             let color = match collider.mask() {
                 ColliderMask::None => Color::new_rgb(255, 255, 255),
@@ -94,24 +95,18 @@ pub(crate) fn debug_colliders_system(data: &mut GameData) {
             let offset = collider.offset();
             let polygon_collider =
                 Polygon::new(collider.collider_coordinates(0.,0.)).pivot(collider.get_pivot());
-            debug_lines_to_add.push((
+            commands.spawn((
                 Parent(entity),
+                Name::new("collider_debug"),
                 ColliderDebug,
                 Transform::from_xyz(offset.x(), offset.y(), 30),
                 polygon_collider,
                 Material::Diffuse(color),
             ));
         } else if !collider.debug_lines() && !global_debug_activated && collider_debug.0.contains(&entity) {
-            debug_lines_to_remove.push(entity);
+            commands.despawn(collider_debug.1.remove(&entity).expect(""));
         }
     }
-
-    debug_lines_to_add.drain(0..).for_each(|components| {
-        data.push(components);
-    });
-    debug_lines_to_remove.drain(0..).for_each(|e| {
-        let _r = data.remove(collider_debug.1.remove(&e).expect(""));
-    });
 }
 
 /// System responsible to add the UiComponent to any T missing its uiComponent
@@ -152,6 +147,7 @@ mod tests {
         transform::Transform,
     };
     use crate::core::components::maths::collider::CollisionArea;
+    use crate::core::resources::commands::{apply_commands, Commands};
     use crate::core::resources::inputs::inputs_controller::InputsController;
     use crate::core::world::GameData;
 
@@ -218,15 +214,18 @@ mod tests {
         let mut world = GameData::default();
         world.insert_resource(InputsController::default());
         world.insert_resource(GlobalStorage::default());
+        world.insert_resource(Commands::default());
 
-        let _collider = world.push((
+        let collider = world.push((
             Transform::default(),
             Collider::new(ColliderMask::None, vec![], ColliderType::SquareCollider(100)).with_debug_lines(),
         ));
 
         debug_colliders_system(&mut world);
+        apply_commands(&mut world);
 
         let res = world.query::<(&ColliderDebug, &Parent)>().iter().count();
         assert_eq!(1, res);
+        assert!(world.find_by_path_from(collider, "collider_debug").is_some());
     }
 }
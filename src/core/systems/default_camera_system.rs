@@ -14,7 +14,7 @@ pub(crate) fn default_camera_system(data: &mut GameData) {
     let (subworld, resources) = data.split();
 
     if let Some(e) = default_entity {
-        debug!("Adding default camera to the entity {:?}", e);
+        debug!("Adding default camera to the entity {}", subworld.entity_label(e));
         let window = resources.window();
         let mut camera = Camera::new(
             window.width() as f32,
@@ -29,12 +29,8 @@ pub(crate) fn hide_propagation_system(data: &mut GameData) {
 pub(crate) fn hide_propagated_deletion_system(data: &mut GameData) {
     let mut child_to_clear = Vec::new();
     for (e, (_c, parent)) in data.query::<(&HidePropagated, &Parent)>().iter() {
-        if data
-            .entry::<&Hide>(parent.0)
-            .expect("Unreachable parent during hide propagated deletion")
-            .get()
-            .is_none()
-        {
+        // A removed parent no longer hides its children
+        if data.entry::<&Hide>(parent.0).map_or(true, |mut hide| hide.get().is_none()) {
             child_to_clear.push(e);
         }
     }
@@ -6,6 +6,8 @@ use crate::core::world::{GameData, World};
 /// System responsible to add/modify Children components to the entities referenced by a Parent component
 /// If the parent component referenced by the Children one is not found, then it deletes the entity
 /// If an entity has a Children component referencing non existing children, then this system will remove these references
+/// It polls the Parent components instead of observing them, so that the ones added or removed through the `SubWorld`
+/// of [`GameData::split`] are handled too
 pub(crate) fn children_manager_system(data: &mut GameData) {
     let mut parents = fetch_parent_entities(data);
     let mut component_to_add = HashMap::new();
@@ -34,8 +36,9 @@ pub(crate) fn children_manager_system(data: &mut GameData) {
         let _r = data.remove_component::<Children>(e);
     });
     entities_to_remove.drain().for_each(|e| {
-        let _r = data.remove(e);
+        let _r = data.remove_recursive(e);
     });
+    data.subworld.hierarchy_updated();
 }
 
 fn fetch_parent_entities(data: &mut GameData) -> HashMap<hecs::Entity, Vec<hecs::Entity>> {
@@ -0,0 +1,9 @@
+use crate::core::components::maths::transform::{Interpolated, Transform};
+use crate::core::world::{GameData, World};
+
+/// System responsible to keep the transform of `Interpolated` entities before a fixed update
+pub(crate) fn interpolation_snapshot_system(data: &mut GameData) {
+    for (_, (transform, interpolated)) in data.query_mut::<(&Transform, &mut Interpolated)>() {
+        interpolated.previous = Some((transform.global_translation, transform.global_angle));
+    }
+}
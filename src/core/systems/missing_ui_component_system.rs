@@ -1,12 +1,32 @@
 use std::any;
 
-use hecs::Component;
+use hecs::{Component, Entity};
 use log::trace;
 
 use crate::graphics::components::ui::{Focusable, UiComponent, UiFocusable};
+use crate::graphics::components::ui::ui_button::UiButton;
+use crate::graphics::components::ui::ui_image::UiImage;
+use crate::graphics::components::ui::ui_text::{UiText, UiTextImage};
+use crate::core::resources::observers::Observers;
 use crate::core::world::{GameData, World};
 
-/// System responsible to add the UiComponent to any T missing its uiComponent
+/// Registers the observers adding the UiComponent to the entities receiving a ui component without it
+pub(crate) fn add_ui_component_observers(observers: &mut Observers) {
+    observers.on_add::<UiImage, _>(add_missing_ui_component);
+    observers.on_add::<UiTextImage, _>(add_missing_ui_component);
+    observers.on_add::<UiText, _>(add_missing_ui_component);
+    observers.on_add::<UiButton, _>(add_missing_ui_component);
+}
+
+fn add_missing_ui_component(data: &mut GameData, entity: Entity) {
+    if data.entry::<&UiComponent>(entity).is_ok_and(|mut entry| entry.get().is_none()) {
+        let _r = data.add_components(entity, (UiComponent,));
+    }
+}
+
+/// System responsible to add the UiComponent to any T missing its uiComponent.
+/// Fallback of the observers for the components added through the `SubWorld` of [`GameData::split`], which are not
+/// observed.
 pub(crate) fn missing_ui_component_system<T: Component>(data: &mut GameData) {
     let mut to_add = Vec::new();
     {
@@ -28,7 +48,7 @@ pub(crate) fn missing_focus_component_system<T: Component + Focusable>(data: &mu
         }
     }
     to_add.drain(0..).for_each(|(e, tab_index)| {
-        trace!("Adding UiFocusable component to entity of type {:?}", any::type_name::<T>());
+        trace!("Adding UiFocusable component to entity {} of type {:?}", data.entity_label(e), any::type_name::<T>());
         let _r = data.add_components(e, (UiFocusable{ rank: tab_index, focused: false },));
     });
 }
@@ -39,16 +59,36 @@ mod tests {
     use crate::graphics::components::ui::font::Font;
     use crate::graphics::components::ui::ui_input::UiInput;
     use crate::core::resources::asset_manager::AssetManager;
+    use crate::core::resources::observers::EngineObservers;
     use crate::core::world::World;
 
     use super::*;
 
     #[test]
-    fn missing_ui_comp_system_test() {
+    fn missing_ui_comp_observer_test() {
         let mut world = GameData::default();
+        let mut engine_observers = EngineObservers::default();
+        add_ui_component_observers(&mut engine_observers.0);
+        world.insert_resource(engine_observers);
+        // Replacing the user observers keeps the engine ones
+        world.insert_resource(Observers::default());
 
         let e = world.push((UiImage::new(1., 1.),));
+        assert!(world.entry::<&UiComponent>(e).expect("").get().is_some());
+
+        let e = world.push((1,));
+        let _r = world.add_components(e, (UiImage::new(1., 1.),));
+        assert!(world.entry::<&UiComponent>(e).expect("").get().is_some());
+    }
+
+    #[test]
+    fn missing_ui_comp_system_test() {
+        let mut world = GameData::default();
+        let mut engine_observers = EngineObservers::default();
+        add_ui_component_observers(&mut engine_observers.0);
+        world.insert_resource(engine_observers);
 
+        let e = world.split().0.push((UiImage::new(1., 1.),));
         assert!(world.entry::<&UiComponent>(e).expect("").get().is_none());
 
         missing_ui_component_system::<UiImage>(&mut world);
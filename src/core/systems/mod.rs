@@ -11,15 +11,20 @@ use crate::graphics::components::ui::ui_text::{UiText, UiTextImage};
 use crate::core::package::Package;
 use crate::core::resources::asset_manager::AssetManager;
 use crate::core::resources::audio::Audio;
+use crate::core::resources::commands::Commands;
 use crate::core::resources::events::Events;
 use crate::core::resources::events::topic::TopicConfiguration;
 use crate::core::resources::focus_manager::FocusManager;
 use crate::core::resources::font_atlas::FontAtlas;
 use crate::core::resources::global_storage::GlobalStorage;
 use crate::core::resources::inputs::inputs_controller::InputsController;
+use crate::core::resources::observers::{EngineObservers, Observers};
+use crate::core::resources::profiler::Profiler;
 use crate::core::resources::time::{Time, Timers, TimerType};
 use crate::core::scene::SceneController;
-use crate::core::state::GameState;
+use crate::core::scheduler::{Stage, System, SystemConfig, SystemConfigBuilder};
+use crate::core::snapshot::SnapshotRegistry;
+use crate::core::state::{GameState, GAME_STATE_TOPIC};
 use crate::core::systems::animations_system::animation_executer_system;
 use crate::core::systems::asset_ref_resolver_system::asset_ref_resolver_system;
 use crate::core::systems::asset_ref_resolver_system::MaterialAssetResolverFn;
@@ -30,7 +35,8 @@ use crate::core::systems::hide_propagation_system::{
     hide_propagated_deletion_system, hide_propagation_system,
 };
 use crate::core::systems::hierarchy_system::children_manager_system;
-use crate::core::systems::missing_ui_component_system::{missing_focus_component_system, missing_ui_component_system};
+use crate::core::systems::missing_ui_component_system::{add_ui_component_observers, missing_focus_component_system, missing_ui_component_system};
+use crate::core::systems::name_index_system::name_index_system;
 use crate::core::systems::parent_transform_system::{dirty_child_system, dirty_transform_system};
 use crate::core::systems::ui_button_systems::{compute_hover, set_childs_on_buttons};
 use crate::core::systems::ui_input_systems::{register_keyboard_inputs_on_ui_input, set_childs_on_inputs, synchronize_input_and_text};
@@ -44,7 +50,9 @@ pub(crate) mod collider_systems;
 pub(crate) mod default_camera_system;
 pub(crate) mod hide_propagation_system;
 pub(crate) mod hierarchy_system;
+pub(crate) mod interpolation_system;
 pub(crate) mod missing_ui_component_system;
+pub(crate) mod name_index_system;
 pub(crate) mod parent_transform_system;
 pub(crate) mod ui_text_system;
 pub(crate) mod ui_input_systems;
@@ -53,6 +61,11 @@ pub(crate) mod ui_button_systems;
 
 
 
+/// Internal systems are labelled with their function name, see [`Stage`] for the stage they are executed in
+fn internal_system<S: System + 'static>(system: S, label: &'static str, stage: Stage) -> SystemConfig {
+    SystemConfigBuilder::new(system).with_stage(stage).with_label(label).get()
+}
+
 pub(crate) struct InternalPackage;
 impl Package for InternalPackage {
     fn prepare(&self, data: &mut GameData) {
@@ -60,6 +73,9 @@ impl Package for InternalPackage {
         events
             .create_topic("Inputs", TopicConfiguration::default())
             .expect("Error while creating topic for inputs event");
+        events
+            .create_topic(GAME_STATE_TOPIC, TopicConfiguration::default())
+            .expect("Error while creating topic for game state event");
 
         let mut timers = Timers::default();
 
@@ -78,40 +94,48 @@ impl Package for InternalPackage {
         data.insert_resource(Audio::default());
         data.insert_resource(FontAtlas::default());
         data.insert_resource(GlobalStorage::default());
+        data.insert_resource(Profiler::default());
+        data.insert_resource(Commands::default());
+        data.insert_resource(Observers::default());
+        let mut engine_observers = EngineObservers::default();
+        add_ui_component_observers(&mut engine_observers.0);
+        data.insert_resource(engine_observers);
+        data.insert_resource(SnapshotRegistry::default());
     }
 
     fn load(&self, builder: ScionBuilder) -> ScionBuilder {
 
         builder
-            .with_system(collider_cleaner_system)
-            .with_system(default_camera_system)
-            .with_system(sync_text_value_system)
-            .with_system(ui_text_bitmap_update_system)
-            .with_system(children_manager_system)
-            .with_system(hide_propagated_deletion_system)
-            .with_system(hide_propagation_system)
-            .with_system(collider_pivot_propagation_system::<Sprite>)
-            .with_system(collider_pivot_propagation_system::<Rectangle>)
-            .with_system(collider_pivot_propagation_system::<Square>)
-            .with_system(collider_pivot_propagation_system::<Triangle>)
-            .with_system(collider_pivot_propagation_system::<Polygon>)
-            .with_system(collider_pivot_propagation_system::<Line>)
-            .with_system(debug_colliders_system)
-            .with_system(missing_ui_component_system::<UiImage>)
-            .with_system(missing_ui_component_system::<UiTextImage>)
-            .with_system(missing_ui_component_system::<UiText>)
-            .with_system(missing_ui_component_system::<UiButton>)
-            .with_system(missing_focus_component_system::<UiInput>)
-            .with_system(asset_ref_resolver_system::<Material, MaterialAssetResolverFn>)
-            .with_system(animation_executer_system)
-            .with_system(dirty_child_system)
-            .with_system(dirty_transform_system)
-            .with_system(compute_collisions_system)
-            .with_system(set_childs_on_inputs)
-            .with_system(set_childs_on_buttons)
-            .with_system(compute_hover)
-            .with_system(focus_switcher_system)
-            .with_system(register_keyboard_inputs_on_ui_input)
-            .with_system(synchronize_input_and_text)
+            .with_system_config(internal_system(name_index_system, "name_index_system", Stage::PreUpdate))
+            .with_system_config(internal_system(collider_cleaner_system, "collider_cleaner_system", Stage::PreUpdate))
+            .with_system_config(internal_system(default_camera_system, "default_camera_system", Stage::PreUpdate))
+            .with_system_config(internal_system(collider_pivot_propagation_system::<Sprite>, "collider_pivot_propagation_system", Stage::PreUpdate))
+            .with_system_config(internal_system(collider_pivot_propagation_system::<Rectangle>, "collider_pivot_propagation_system", Stage::PreUpdate))
+            .with_system_config(internal_system(collider_pivot_propagation_system::<Square>, "collider_pivot_propagation_system", Stage::PreUpdate))
+            .with_system_config(internal_system(collider_pivot_propagation_system::<Triangle>, "collider_pivot_propagation_system", Stage::PreUpdate))
+            .with_system_config(internal_system(collider_pivot_propagation_system::<Polygon>, "collider_pivot_propagation_system", Stage::PreUpdate))
+            .with_system_config(internal_system(collider_pivot_propagation_system::<Line>, "collider_pivot_propagation_system", Stage::PreUpdate))
+            .with_system_config(internal_system(missing_focus_component_system::<UiInput>, "missing_focus_component_system", Stage::PreUpdate))
+            .with_system_config(internal_system(animation_executer_system, "animation_executer_system", Stage::PreUpdate))
+            .with_system_config(internal_system(compute_collisions_system, "compute_collisions_system", Stage::PreUpdate))
+            .with_system_config(internal_system(set_childs_on_inputs, "set_childs_on_inputs", Stage::PreUpdate))
+            .with_system_config(internal_system(set_childs_on_buttons, "set_childs_on_buttons", Stage::PreUpdate))
+            .with_system_config(internal_system(compute_hover, "compute_hover", Stage::PreUpdate))
+            .with_system_config(internal_system(focus_switcher_system, "focus_switcher_system", Stage::PreUpdate))
+            .with_system_config(internal_system(register_keyboard_inputs_on_ui_input, "register_keyboard_inputs_on_ui_input", Stage::PreUpdate))
+            .with_system_config(internal_system(synchronize_input_and_text, "synchronize_input_and_text", Stage::PreUpdate))
+            .with_system_config(internal_system(sync_text_value_system, "sync_text_value_system", Stage::PostUpdate))
+            .with_system_config(internal_system(ui_text_bitmap_update_system, "ui_text_bitmap_update_system", Stage::PostUpdate))
+            .with_system_config(internal_system(debug_colliders_system, "debug_colliders_system", Stage::PostUpdate))
+            .with_system_config(internal_system(missing_ui_component_system::<UiImage>, "missing_ui_component_system", Stage::PostUpdate))
+            .with_system_config(internal_system(missing_ui_component_system::<UiTextImage>, "missing_ui_component_system", Stage::PostUpdate))
+            .with_system_config(internal_system(missing_ui_component_system::<UiText>, "missing_ui_component_system", Stage::PostUpdate))
+            .with_system_config(internal_system(missing_ui_component_system::<UiButton>, "missing_ui_component_system", Stage::PostUpdate))
+            .with_system_config(internal_system(asset_ref_resolver_system::<Material, MaterialAssetResolverFn>, "asset_ref_resolver_system", Stage::PostUpdate))
+            .with_system_config(internal_system(children_manager_system, "children_manager_system", Stage::PreRender))
+            .with_system_config(internal_system(hide_propagated_deletion_system, "hide_propagated_deletion_system", Stage::PreRender))
+            .with_system_config(internal_system(hide_propagation_system, "hide_propagation_system", Stage::PreRender))
+            .with_system_config(internal_system(dirty_child_system, "dirty_child_system", Stage::PreRender))
+            .with_system_config(internal_system(dirty_transform_system, "dirty_transform_system", Stage::PreRender))
     }
 }
@@ -0,0 +1,7 @@
+use crate::core::world::GameData;
+
+/// System responsible of indexing again the [`crate::core::components::name::Name`]s and
+/// [`crate::core::components::name::Tags`] replaced in place, so that the entities are found by their new ones
+pub(crate) fn name_index_system(data: &mut GameData) {
+    data.subworld.reindex_names();
+}
@@ -45,6 +45,7 @@ pub(crate) fn dirty_child_system(data: &mut GameData) {
                                 .compute_global_from_parent(parent_transform.global_translation());
                             child_transform.compute_global_angle_from_parent(parent_transform.global_angle);
                             parents_transform.insert(child, *child_transform);
+                            data.changes().mark_changed::<Transform>(child);
                         } else {
                             // Else we need to check the parent first, in the next iteration
                             parent_to_check.push((child, parent));
@@ -61,24 +62,31 @@ pub(crate) fn dirty_child_system(data: &mut GameData) {
 }
 
 /// System responsible of detecting when a child transform should be computed again based on any parent
-/// transform modification
+/// transform modification.
+///
+/// Transforms are mutated in place through their setters, which only flag them as dirty. This system reports every
+/// dirty transform to the [`crate::core::change_detection::ChangeTracker`], so that `Changed<Transform>` sees them.
 pub(crate) fn dirty_transform_system(data: &mut GameData) {
     let mut first_iter = true;
     let mut transform_entities: Vec<(Transform, Vec<hecs::Entity>)> = Vec::new();
+    let mut changed_entities = Vec::new();
     while first_iter || !transform_entities.is_empty() {
         first_iter = false;
         while let Some((transform, entities)) = transform_entities.pop() {
             for entity in entities {
+                trace!("Updating child Transform of entity {}, because parent was marked as dirty", data.entity_label(entity));
                 if let Ok(child_transform) = data.entry_mut::<&mut Transform>(entity) {
-                    trace!("Updating child Transform of entity {:?}, because parent was marked as dirty", entity);
                     child_transform.compute_global_from_parent(transform.global_translation());
                     child_transform.compute_global_angle_from_parent(transform.global_angle);
                 }
             }
         }
-        for (_, (parent_transform, children)) in
+        for (entity, (parent_transform, children)) in
             data.query_mut::<(&mut Transform, Option<&Children>)>()
         {
+            if parent_transform.dirty {
+                changed_entities.push(entity);
+            }
             if let Some(children) = children {
                 if parent_transform.dirty {
                     transform_entities.push((*parent_transform, children.0.clone()));
@@ -87,6 +95,7 @@ pub(crate) fn dirty_transform_system(data: &mut GameData) {
             parent_transform.dirty = false;
         }
     }
+    changed_entities.into_iter().for_each(|entity| data.changes().mark_changed::<Transform>(entity));
 }
 
 #[cfg(test)]
@@ -210,4 +219,25 @@ mod tests {
         );
 
     }
+
+    #[test]
+    fn dirty_transforms_are_reported_as_changed_test() {
+        let mut world = GameData::default();
+        let parent = world.push((Transform::from_xyz(1., 1., 1),));
+        let child = world.push((Transform::from_xyz(1., 1., 1), Parent(parent)));
+        let other = world.push((Transform::from_xyz(1., 1., 1),));
+        children_manager_system(&mut world);
+        dirty_child_system(&mut world);
+        dirty_transform_system(&mut world);
+
+        let tick = world.changes().change_tick();
+        world.changes().begin_system();
+        world.entry_mut::<&mut Transform>(parent).unwrap().append_x(2.);
+        dirty_transform_system(&mut world);
+
+        let changed = world.changes().changed::<Transform>(tick);
+        assert!(changed.contains(&parent));
+        assert!(changed.contains(&child));
+        assert!(!changed.contains(&other));
+    }
 }
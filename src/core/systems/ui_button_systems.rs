@@ -1,7 +1,7 @@
 use hecs::Entity;
 use winit::window::CursorIcon;
 
-use crate::graphics::components::{Hide, HidePropagated};
+use crate::graphics::components::{Hide, HiddenByScene, HidePropagated};
 use crate::graphics::components::color::Color;
 use crate::graphics::components::material::Material;
 use crate::core::components::maths::hierarchy::{Children, Parent};
@@ -15,6 +15,8 @@ use crate::core::world::{GameData, Resources, SubWorld, World};
 
 /// This system is responsible of handling components needed to represent buttons
 /// It will detect and create needed components
+/// It polls the buttons without Children instead of observing the UiButton components, so that the buttons added
+/// through the `SubWorld` of [`GameData::split`] are handled too
 pub(crate) fn set_childs_on_buttons(data: &mut GameData) {
     let (world, resources) = data.split();
 
@@ -52,7 +54,8 @@ pub(crate) fn compute_hover(data: &mut GameData) {
     for (_, (ui_button, transform, children))
     in world.query_mut::<(&mut UiButton, &Transform, &mut Children)>()
         .without::<&Hide>()
-        .without::<&HidePropagated>() {
+        .without::<&HidePropagated>()
+        .without::<&HiddenByScene>() {
         if transform.global_translation.x as f64 <= mx
             && (transform.global_translation.x + ui_button.width() as f32) as f64 >= mx
             && transform.global_translation.y as f64 <= my
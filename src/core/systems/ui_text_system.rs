@@ -26,22 +26,48 @@ pub(crate) fn sync_text_value_system(data: &mut GameData) {
         if let Some(function) = ui_text.sync_fn {
             ui_text.set_text(function(resources));
         }
+        if let Some(key) = ui_text.sync_key.clone() {
+            let state = resources.game_state();
+            if ui_text.synced_version.is_none_or(|version| state.changed_since(&key, version)) {
+                ui_text.set_text(state.get(&key).map(|value| value.to_string()).unwrap_or_default());
+                ui_text.synced_version = Some(state.version());
+            }
+        }
     }
 }
 
+/// Replaces the glyphs of the dirty texts. The glyphs are despawned and spawned through the [`crate::core::resources::commands::Commands`],
+/// so they are visible once the commands of the stage are applied, before the hierarchy and transforms systems of `PreRender`.
 pub(crate) fn ui_text_bitmap_update_system(data: &mut GameData) {
-    let mut parent_to_remove: HashSet<Entity> = HashSet::new();
-    let mut to_add: Vec<(UiTextImage, Material, UiComponent, Transform, Parent)> = Vec::new();
     let (world, resources) = data.split();
+    let mut commands = resources.commands();
+
+    let dirty_texts = world
+        .query::<(&UiText, &Transform)>()
+        .iter()
+        .filter(|(_, (ui_text, _))| ui_text.dirty)
+        .map(|(e, _)| e)
+        .collect::<HashSet<Entity>>();
+    if dirty_texts.is_empty() {
+        return;
+    }
+    let glyphs_to_remove = world
+        .query::<(&UiTextImage, &Parent)>()
+        .iter()
+        .filter(|(_, (_, p))| dirty_texts.contains(&p.0))
+        .map(|(e, _)| e)
+        .collect::<Vec<_>>();
+    commands.despawn_all_recursive(glyphs_to_remove);
 
     for (e, (ui_text, transform)) in world.query_mut::<(&mut UiText, &Transform)>() {
         if ui_text.dirty {
-            parent_to_remove.insert(e);
             let font = resources.assets_mut().get_font_for_ref(ui_text.font_ref());
 
             match font {
                 Font::Bitmap { texture_path, chars, width, height, texture_columns, texture_lines } => {
-                    to_add.append(&mut update_bitmap(texture_path, chars, width, height, texture_columns, texture_lines, ui_text, transform, e));
+                    update_bitmap(texture_path, chars, width, height, texture_columns, texture_lines, ui_text, transform, e)
+                        .into_iter()
+                        .for_each(|glyph| commands.spawn(glyph));
                 }
                 Font::TrueType { font_path } => {
                     let mut font_atlas = resources.font_atlas();
@@ -56,7 +82,6 @@ pub(crate) fn ui_text_bitmap_update_system(data: &mut GameData) {
                     let true_type_data = font_atlas.get_texture(&font_path, ui_text.font_size(), &color).expect("Missing data from atlas after insert");
 
 
-                    let mut to_add_secondary: Vec<(UiTextImage, Material, UiComponent, Transform, Parent)> = Vec::new();
                     let texture_width = true_type_data.width as f32;
                     let texture_height = true_type_data.height  as f32;
                     let mut current_pos = 0.;
@@ -89,7 +114,7 @@ pub(crate) fn ui_text_bitmap_update_system(data: &mut GameData) {
                         char_transform.set_z(transform.translation().z()+1);
                         char_transform.append_x(ui_text.padding().left_or_zero());
                         char_transform.append_y(ui_text.padding().top_or_zero());
-                        to_add_secondary.push((
+                        commands.spawn((
                             UiTextImage(UiImage::new_with_uv_map(
                                 char.end_x-char.start_x,
                                 char.end_y-char.start_y,
@@ -101,26 +126,11 @@ pub(crate) fn ui_text_bitmap_update_system(data: &mut GameData) {
                             Parent(e),
                         ));
                     }
-                    to_add.append(&mut to_add_secondary);
                 }
             };
             ui_text.dirty = false;
         }
     }
-    let entities_to_remove = data
-        .query::<( &UiTextImage, &Parent) > ()
-        .iter()
-        .filter( | (_e, (_, p)) | parent_to_remove.contains( & p.0))
-        .map( | (e, _) | e)
-        .collect::<Vec<_ > > ();
-
-    entities_to_remove.iter().for_each( | e| {
-        let _r = data.remove( * e);
-    });
-
-    to_add.drain(0..).for_each( | c| {
-        data.push(c);
-    });
 }
 
 fn add_font_to_atlas_if_missing(size: usize, color: &Color, font_path: &str,  font_atlas: &mut AtomicRefMut<FontAtlas>) {
@@ -200,6 +210,8 @@ fn update_bitmap(texture_path: String,
             },
         };
         use crate::core::resources::asset_manager::AssetManager;
+        use crate::core::resources::commands::{apply_commands, Commands};
+        use crate::core::state::GameState;
         use crate::core::world::World;
 
         use super::*;
@@ -223,11 +235,13 @@ fn update_bitmap(texture_path: String,
         #[test]
         fn ui_text_without_transform_should_not_generate_ui_image() {
             let mut world = GameData::default();
+            world.insert_resource(Commands::default());
             let mut manager = AssetManager::default();
             let _entity = world.push((get_test_ui_text(&mut manager), ));
             world.insert_resource(manager);
 
             ui_text_bitmap_update_system(&mut world);
+            apply_commands(&mut world);
 
             let cpt = world.query::<&UiTextImage>().iter().count();
             assert_eq!(0, cpt);
@@ -236,17 +250,39 @@ fn update_bitmap(texture_path: String,
         #[test]
         fn ui_text_with_transform_should_generate_ui_image() {
             let mut world = GameData::default();
+            world.insert_resource(Commands::default());
 
             let mut manager = AssetManager::default();
             let _entity = world.push((get_test_ui_text(&mut manager), Transform::default()));
             world.insert_resource(manager);
 
             ui_text_bitmap_update_system(&mut world);
+            assert_eq!(0, world.query::<&UiTextImage>().iter().count());
+            apply_commands(&mut world);
 
             let cpt = world.query::<&UiTextImage>().iter().count();
             assert_eq!(3, cpt);
         }
 
+        #[test]
+        fn ui_text_update_should_replace_ui_images() {
+            let mut world = GameData::default();
+            world.insert_resource(Commands::default());
+
+            let mut manager = AssetManager::default();
+            let entity = world.push((get_test_ui_text(&mut manager), Transform::default()));
+            world.insert_resource(manager);
+            ui_text_bitmap_update_system(&mut world);
+            apply_commands(&mut world);
+
+            world.entry_mut::<&mut UiText>(entity).unwrap().set_text("ab".to_string());
+            ui_text_bitmap_update_system(&mut world);
+            apply_commands(&mut world);
+
+            assert_eq!(2, world.query::<&UiTextImage>().iter().count());
+            assert!(world.query::<&Parent>().iter().all(|(_, parent)| parent.0 == entity));
+        }
+
         struct Test {
             pub score: usize,
         }
@@ -270,4 +306,26 @@ fn update_bitmap(texture_path: String,
             let txt = world.query::<&UiText>().iter().next().unwrap().1.text().to_string();
             assert_eq!("5".to_string(), txt);
         }
+
+        #[test]
+        fn ui_text_synchronized_with_game_state() {
+            let mut world = GameData::default();
+            world.insert_resource(GameState::default());
+            world.game_state_mut().set("score", 5);
+
+            let mut manager = AssetManager::default();
+            let entity = world.push((get_test_ui_text(&mut manager).sync_state("score"), Transform::default()));
+            world.insert_resource(manager);
+
+            sync_text_value_system(&mut world);
+            assert_eq!("5", world.entry_mut::<&mut UiText>(entity).unwrap().text().as_str());
+
+            world.entry_mut::<&mut UiText>(entity).unwrap().set_text("edited".to_string());
+            sync_text_value_system(&mut world);
+            assert_eq!("edited", world.entry_mut::<&mut UiText>(entity).unwrap().text().as_str());
+
+            world.game_state_mut().add_int("score", 2);
+            sync_text_value_system(&mut world);
+            assert_eq!("7", world.entry_mut::<&mut UiText>(entity).unwrap().text().as_str());
+        }
     }
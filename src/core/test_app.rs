@@ -0,0 +1,223 @@
+//! A steppable, headless version of a `Scion` application, made to write deterministic integration tests.
+
+use std::time::Duration;
+
+use crate::core::resources::inputs::mouse::MouseEvent;
+use crate::core::resources::inputs::types::{InputState, KeyboardEvent, KeyCode, MouseButton};
+use crate::core::scion_runner::ScionRunner;
+use crate::core::world::GameData;
+use crate::utils::frame_limiter::FixedTimestep;
+
+/// `ScionTestApp` runs the game loop of a `Scion` application frame by frame, without any window,
+/// rendering or audio, and using a fixed injected frame duration instead of the wall clock.
+/// It is obtained by calling [`crate::ScionBuilder::build_test_app`].
+///
+/// Each call to [`ScionTestApp::step`] executes, in order : the scene `on_update`, the systems, the scene systems,
+/// the scene `late_update`, as many scene `on_fixed_update` as the frame duration requires (using the configured
+/// fixed update rate) and finally the end of frame (inputs and events cleanup, scene switching).
+pub struct ScionTestApp {
+    runner: ScionRunner,
+    frame_duration: Duration,
+    fixed_timestep: FixedTimestep,
+}
+
+impl ScionTestApp {
+    pub(crate) fn new(mut runner: ScionRunner) -> Self {
+        let fixed_timestep = FixedTimestep::from_config(&runner.frame_limiter_config);
+        runner.time().set_fixed_delta_duration(fixed_timestep.fixed_duration());
+        runner.setup();
+        Self { runner, frame_duration: Duration::from_secs(1) / 60, fixed_timestep }
+    }
+
+    /// Sets the duration injected as delta for each stepped frame. Default is 1/60 second.
+    pub fn with_frame_duration(mut self, frame_duration: Duration) -> Self {
+        self.frame_duration = frame_duration;
+        self
+    }
+
+    /// Executes a single frame
+    pub fn step(&mut self) {
+        let frame_duration = self.runner.time().frame_with_duration(self.frame_duration);
+        self.runner.update(frame_duration);
+        for _ in 0..self.fixed_timestep.accumulate(frame_duration) {
+            self.runner.fixed_update();
+        }
+        self.runner.time().set_interpolation_alpha(self.fixed_timestep.alpha());
+        self.runner.end_frame();
+    }
+
+    /// Executes `frames` frames
+    pub fn step_frames(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    /// Whether [`GameData::quit`] has been called, meaning that a real app would have stopped after the current frame
+    pub fn quit_requested(&self) -> bool {
+        self.runner.game_data.quit_requested()
+    }
+
+    /// Stops the app as a real app would do when quitting : the current scene is stopped.
+    pub fn shutdown(mut self) -> GameData {
+        self.runner.shutdown(None);
+        self.runner.game_data
+    }
+
+    /// Retrieves the game data, to inspect the world and resources between steps
+    pub fn game_data(&self) -> &GameData {
+        &self.runner.game_data
+    }
+
+    /// Retrieves the game data mutable, to modify the world and resources between steps
+    pub fn game_data_mut(&mut self) -> &mut GameData {
+        &mut self.runner.game_data
+    }
+
+    /// Injects a keyboard event that will be visible during the next step
+    pub fn inject_keyboard_event(&mut self, event: KeyboardEvent) {
+        self.runner.game_data.inputs().add_keyboard_event(event);
+    }
+
+    /// Convenience function to inject a key press during the next step
+    pub fn press_key(&mut self, keycode: KeyCode) {
+        self.inject_keyboard_event(KeyboardEvent { keycode, state: InputState::Pressed });
+    }
+
+    /// Convenience function to inject a key release during the next step
+    pub fn release_key(&mut self, keycode: KeyCode) {
+        self.inject_keyboard_event(KeyboardEvent { keycode, state: InputState::Released });
+    }
+
+    /// Injects a mouse button event that will be visible during the next step
+    pub fn inject_mouse_event(&mut self, button: MouseButton, state: InputState) {
+        self.runner.game_data.inputs().add_click_event(MouseEvent { button, state });
+    }
+
+    /// Moves the mouse cursor to the given position
+    pub fn set_mouse_position(&mut self, x: f64, y: f64) {
+        self.runner.game_data.inputs().set_mouse_position(x, y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::scion_config::ScionConfigBuilder;
+    use crate::core::resources::inputs::types::Input;
+    use crate::core::resources::time::Time;
+    use crate::core::scene::Scene;
+    use crate::core::system_param::ResMut;
+    use crate::ScionBuilder;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct Counters {
+        updates: usize,
+        fixed_updates: usize,
+        systems: usize,
+        space_pressed: usize,
+    }
+
+    #[derive(Default)]
+    struct CountingScene;
+
+    impl Scene for CountingScene {
+        fn on_start(&mut self, data: &mut GameData) {
+            data.insert_resource(Counters::default());
+        }
+
+        fn on_stop(&mut self, data: &mut GameData) {
+            data.game_state_mut().set_bool("stopped", true);
+        }
+
+        fn on_update(&mut self, data: &mut GameData) {
+            data.get_resource_mut::<Counters>().unwrap().updates += 1;
+        }
+
+        fn on_fixed_update(&mut self, data: &mut GameData) {
+            data.get_resource_mut::<Counters>().unwrap().fixed_updates += 1;
+        }
+    }
+
+    fn counting_system(data: &mut GameData) {
+        let pressed = data.inputs().input_pressed_event(&Input::Key(KeyCode::Space));
+        let mut counters = data.get_resource_mut::<Counters>().unwrap();
+        counters.systems += 1;
+        if pressed {
+            counters.space_pressed += 1;
+        }
+    }
+
+    #[test]
+    fn step_frames_test() {
+        let mut app = ScionBuilder::new(ScionConfigBuilder::new().without_window().get())
+            .with_scene::<CountingScene>()
+            .with_system(counting_system)
+            .build_test_app()
+            .with_frame_duration(Duration::from_millis(10));
+
+        app.step_frames(3);
+        app.press_key(KeyCode::Space);
+        app.step();
+        app.step();
+
+        let counters = app.game_data().get_resource::<Counters>().unwrap();
+        assert_eq!(5, counters.updates);
+        // 50ms at the default 60 fixed updates per second
+        assert_eq!(3, counters.fixed_updates);
+        assert_eq!(5, counters.systems);
+        assert_eq!(1, counters.space_pressed);
+
+        let time = app.game_data().get_resource::<Time>().unwrap();
+        assert_eq!(5, time.frame_number());
+        assert_eq!(Duration::from_millis(10), time.delta_duration());
+    }
+
+    fn typed_counting_system(mut counters: ResMut<Counters>) {
+        counters.systems += 1;
+    }
+
+    #[test]
+    fn typed_system_test() {
+        let mut app = ScionBuilder::new(ScionConfigBuilder::new().without_window().get())
+            .with_scene::<CountingScene>()
+            .with_typed_system(typed_counting_system)
+            .build_test_app();
+
+        app.step_frames(2);
+        assert_eq!(2, app.game_data().get_resource::<Counters>().unwrap().systems);
+    }
+
+    #[test]
+    fn profiler_test() {
+        let mut app = ScionBuilder::new(ScionConfigBuilder::new().without_window().get())
+            .with_scene::<CountingScene>()
+            .with_system(counting_system)
+            .with_profiler()
+            .build_test_app();
+
+        app.step_frames(2);
+        let profiler = app.game_data().profiler();
+        assert_eq!(2, profiler.stats("scion::core::test_app::tests::counting_system").unwrap().calls());
+        assert_eq!(2, profiler.stats("default_camera_system").unwrap().calls());
+        assert_eq!(1, profiler.stats("scion::core::test_app::tests::CountingScene::on_start").unwrap().calls());
+        assert_eq!(2, profiler.stats("scion::core::test_app::tests::CountingScene::on_update").unwrap().calls());
+        assert!(profiler.chrome_trace().contains("\"name\":\"default_camera_system\""));
+    }
+
+    #[test]
+    fn quit_and_shutdown_test() {
+        let mut app = ScionBuilder::new(ScionConfigBuilder::new().without_window().get())
+            .with_scene::<CountingScene>()
+            .build_test_app();
+
+        app.step();
+        assert!(!app.quit_requested());
+        app.game_data().quit();
+        assert!(app.quit_requested());
+
+        let data = app.shutdown();
+        assert!(data.game_state().get_bool("stopped"));
+    }
+}
@@ -10,23 +10,40 @@ use hecs::{
     QueryMut, QueryOne, QueryOneError,
 };
 
+use crate::core::app_state::{AppState, AppStates};
+use crate::core::change_detection::ChangeTracker;
 use crate::core::components::maths::camera::{Camera, DefaultCamera};
+use crate::core::components::maths::hierarchy::{Children, Parent};
+use crate::core::components::name::{Name, NameIndex, Tags};
 use crate::core::resources::asset_manager::AssetManager;
 use crate::core::resources::audio::Audio;
+use crate::core::resources::commands::Commands;
 use crate::core::resources::events::Events;
 use crate::core::resources::focus_manager::FocusManager;
 use crate::core::resources::font_atlas::FontAtlas;
+use crate::core::resources::global_storage::GlobalStorage;
 use crate::core::resources::inputs::inputs_controller::InputsController;
+use crate::core::resources::observers::{notify, observed, Lifecycle, Observers};
+use crate::core::resources::profiler::Profiler;
+use crate::core::resources::save_manager::SaveManager;
 use crate::core::resources::time::Timers;
 use crate::core::resources::window::Window;
 use crate::core::scene::SceneController;
+use crate::core::snapshot::{restore_snapshot, take_snapshot, EntityMap, SnapshotError, SnapshotRegistry, WorldSnapshot};
 use crate::core::state::GameState;
+use crate::utils::ScionError;
 
 pub trait World {
     fn entities(&self) -> HashSet<Entity>;
     fn clear(&mut self);
     fn push(&mut self, components: impl DynamicBundle) -> Entity;
     fn remove(&mut self, entity: Entity) -> Result<(), NoSuchEntity>;
+    /// Removes `entity` and all its descendants, found through their [`Parent`] and [`Children`] components.
+    /// `entity` is also removed from the [`Children`] of its parent.
+    fn remove_recursive(&mut self, entity: Entity) -> Result<(), NoSuchEntity>;
+    /// Removes all the `entities` and their descendants like [`World::remove_recursive`], walking the hierarchy once.
+    /// Entities that don't exist are ignored.
+    fn remove_all_recursive(&mut self, entities: &[Entity]);
     fn add_components(
         &mut self,
         entity: Entity,
@@ -52,6 +69,11 @@ impl GameData {
         (&mut self.subworld, &mut self.resources)
     }
 
+    /// Retrieves the change ticks of the components, see [`crate::core::change_detection`]
+    pub fn changes(&self) -> &ChangeTracker {
+        &self.subworld.changes
+    }
+
     pub fn contains_resource<T: Resource>(&self) -> bool {
         self.resources.internal_resources.storage.contains_key(&ResourceTypeId::of::<T>())
     }
@@ -95,6 +117,12 @@ impl GameData {
             .expect("The engine is missing the mandatory game state resource")
     }
 
+    /// retrieves the application states of type `S` from the resources, see [`crate::ScionBuilder::with_app_state`].
+    pub fn app_state<S: AppState>(&self) -> AtomicRefMut<AppStates<S>> {
+        self.get_resource_mut::<AppStates<S>>()
+            .expect("The application state is missing, it must be registered with `ScionBuilder::with_app_state`")
+    }
+
     /// retrieves the asset manager from the resources.
     pub fn assets(&self) -> AtomicRef<AssetManager> {
         self.get_resource::<AssetManager>()
@@ -143,6 +171,24 @@ impl GameData {
             .expect("The engine is missing the mandatory scene controller resource")
     }
 
+    /// retrieves the profiler from the resources
+    pub fn profiler(&self) -> AtomicRefMut<Profiler> {
+        self.get_resource_mut::<Profiler>()
+            .expect("The engine is missing the mandatory profiler resource")
+    }
+
+    /// retrieves the commands from the resources
+    pub fn commands(&self) -> AtomicRefMut<Commands> {
+        self.get_resource_mut::<Commands>()
+            .expect("The engine is missing the mandatory commands resource")
+    }
+
+    /// retrieves the observers from the resources
+    pub fn observers(&self) -> AtomicRefMut<Observers> {
+        self.get_resource_mut::<Observers>()
+            .expect("The engine is missing the mandatory observers resource")
+    }
+
     /// retrieves the font_atlas from the resources.
     pub(crate) fn font_atlas(&self) -> AtomicRefMut<FontAtlas> {
         self.get_resource_mut::<FontAtlas>()
@@ -156,6 +202,88 @@ impl GameData {
             .expect("The engine is missing the mandatory focus manager resource")
     }
 
+    /// Requests the application to quit at the end of the current frame.
+    /// The current scene will be stopped, and the rendering and audio threads will be shut down before exiting.
+    pub fn quit(&self) {
+        self.get_resource_mut::<GlobalStorage>()
+            .expect("The engine is missing the mandatory global storage resource")
+            .quit_requested = true;
+    }
+
+    pub(crate) fn quit_requested(&self) -> bool {
+        self.get_resource::<GlobalStorage>().is_some_and(|storage| storage.quit_requested)
+    }
+
+    /// retrieves the snapshot registry from the resources
+    pub fn snapshot_registry(&self) -> AtomicRefMut<SnapshotRegistry> {
+        self.get_resource_mut::<SnapshotRegistry>()
+            .expect("The engine is missing the mandatory snapshot registry resource")
+    }
+
+    /// Serializes the entities and resources registered in the [`SnapshotRegistry`], see [`crate::core::snapshot`]
+    pub fn snapshot(&self) -> Result<WorldSnapshot, SnapshotError> {
+        take_snapshot(self)
+    }
+
+    /// Replaces all the entities with the ones of `snapshot`, as well as the resources it contains.
+    /// Returns the new ids of the snapshot entities.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) -> Result<EntityMap, SnapshotError> {
+        restore_snapshot(self, snapshot)
+    }
+
+    /// retrieves the save manager from the resources
+    pub fn save_manager(&self) -> AtomicRefMut<SaveManager> {
+        self.get_resource_mut::<SaveManager>()
+            .expect("The engine is missing the mandatory save manager resource")
+    }
+
+    /// Saves a [`Self::snapshot`] of the game in the save `slot`, see [`SaveManager`]
+    pub fn save_game(&self, slot: &str) -> Result<(), ScionError> {
+        let snapshot = self.snapshot().map_err(|e| ScionError::new(&e.to_string()))?;
+        self.save_manager().write(slot, &snapshot)
+    }
+
+    /// Restores the game saved in the save `slot`, see [`Self::restore`]
+    pub fn load_game(&mut self, slot: &str) -> Result<EntityMap, ScionError> {
+        let snapshot = self.save_manager().read(slot)?;
+        self.restore(&snapshot).map_err(|e| ScionError::new(&e.to_string()))
+    }
+
+    /// Reserves an entity that doesn't exist yet, to be spawned later with [`Commands::insert`]
+    pub fn reserve_entity(&self) -> Entity {
+        self.subworld.internal_world.reserve_entity()
+    }
+
+    /// Retrieves the first entity named `name`, see [`SubWorld::find_by_name`]
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        self.subworld.find_by_name(name)
+    }
+
+    /// Retrieves all the entities named `name`, in the order they were named
+    pub fn find_all_by_name(&self, name: &str) -> Vec<Entity> {
+        self.subworld.find_all_by_name(name)
+    }
+
+    /// Retrieves all the entities having `tag` in their [`Tags`], in the order they were tagged
+    pub fn find_all_with_tag(&self, tag: &str) -> Vec<Entity> {
+        self.subworld.find_all_with_tag(tag)
+    }
+
+    /// Retrieves an entity by the path of names leading to it from a root entity, see [`SubWorld::find_by_path`]
+    pub fn find_by_path(&self, path: &str) -> Option<Entity> {
+        self.subworld.find_by_path(path)
+    }
+
+    /// Retrieves a descendant of `entity` by the path of names leading to it, see [`SubWorld::find_by_path_from`]
+    pub fn find_by_path_from(&self, entity: Entity, path: &str) -> Option<Entity> {
+        self.subworld.find_by_path_from(entity, path)
+    }
+
+    /// Label of `entity` to use in logs, made of its name if it has one and of its id
+    pub fn entity_label(&self, entity: Entity) -> String {
+        self.subworld.entity_label(entity)
+    }
+
     pub(crate) fn has_camera(&self)-> bool{
         self.subworld.query::<&Camera>().iter().count() > 0
     }
@@ -171,15 +299,44 @@ impl World for GameData {
     }
 
     fn clear(&mut self) {
-        self.subworld.internal_world.clear();
+        self.subworld.clear();
     }
 
     fn push(&mut self, components: impl DynamicBundle) -> Entity {
-        self.subworld.internal_world.spawn(components)
+        if !observed(self) {
+            return self.subworld.push(components);
+        }
+        let types = components.with_ids(|ids| ids.to_vec());
+        let entity = self.subworld.push(components);
+        notify(self, entity, Lifecycle::Added(&types));
+        entity
     }
 
     fn remove(&mut self, entity: Entity) -> Result<(), NoSuchEntity> {
-        self.subworld.internal_world.despawn(entity)
+        if observed(self) && self.contains(entity) {
+            notify(self, entity, Lifecycle::Despawned);
+            let types = self.subworld.component_types(entity);
+            notify(self, entity, Lifecycle::Removed(&types));
+            if !self.contains(entity) {
+                // Already removed by an observer
+                return Ok(());
+            }
+        }
+        self.subworld.remove(entity)
+    }
+
+    fn remove_recursive(&mut self, entity: Entity) -> Result<(), NoSuchEntity> {
+        if !self.contains(entity) {
+            return Err(NoSuchEntity);
+        }
+        self.remove_all_recursive(&[entity]);
+        Ok(())
+    }
+
+    fn remove_all_recursive(&mut self, entities: &[Entity]) {
+        self.subworld.detach_subtrees(entities).drain(..).for_each(|e| {
+            let _r = self.remove(e);
+        });
     }
 
     fn add_components(
@@ -187,11 +344,21 @@ impl World for GameData {
         entity: Entity,
         components: impl DynamicBundle,
     ) -> Result<(), NoSuchEntity> {
-        self.subworld.internal_world.insert(entity, components)
+        if !observed(self) {
+            return self.subworld.add_components(entity, components);
+        }
+        let existing = self.subworld.component_types(entity);
+        let added: Vec<TypeId> = components.with_ids(|ids| ids.iter().filter(|id| !existing.contains(id)).copied().collect());
+        self.subworld.add_components(entity, components)?;
+        notify(self, entity, Lifecycle::Added(&added));
+        Ok(())
     }
 
     fn remove_component<T: Component>(&mut self, entity: Entity) -> Result<T, ComponentError> {
-        self.subworld.internal_world.remove_one::<T>(entity)
+        if observed(self) && self.subworld.component_types(entity).contains(&TypeId::of::<T>()) {
+            notify(self, entity, Lifecycle::Removed(&[TypeId::of::<T>()]));
+        }
+        self.subworld.remove_component::<T>(entity)
     }
 
     fn query<Q: Query>(&self) -> QueryBorrow<'_, Q> {
@@ -222,6 +389,161 @@ impl World for GameData {
 #[derive(Default)]
 pub struct SubWorld {
     internal_world: hecs::World,
+    pub(crate) changes: ChangeTracker,
+    names: NameIndex,
+    /// Whether a [`Parent`] was added or replaced since the hierarchy system last updated the [`Children`]
+    hierarchy_outdated: bool,
+}
+
+impl SubWorld {
+    /// Retrieves the change ticks of the components, see [`crate::core::change_detection`]
+    pub fn changes(&self) -> &ChangeTracker {
+        &self.changes
+    }
+
+    /// Retrieves the first entity named `name`. Names don't have to be unique, so when several entities share
+    /// the name, the one that was named first is returned.
+    pub fn find_by_name(&self, name: &str) -> Option<Entity> {
+        self.named(name).next()
+    }
+
+    /// Retrieves all the entities named `name`, in the order they were named
+    pub fn find_all_by_name(&self, name: &str) -> Vec<Entity> {
+        self.named(name).collect()
+    }
+
+    /// Retrieves all the entities having `tag` in their [`Tags`], in the order they were tagged
+    pub fn find_all_with_tag(&self, tag: &str) -> Vec<Entity> {
+        self.names
+            .tagged(tag)
+            .iter()
+            .filter(|e| self.internal_world.get::<&Tags>(**e).is_ok_and(|tags| tags.contains(tag)))
+            .copied()
+            .collect()
+    }
+
+    /// Retrieves an entity by the path of names leading to it, separated by `/`, like `"hud/score"`.
+    /// The first name is the one of an entity without [`Parent`], each following name is the one of a child of
+    /// the previous entity.
+    pub fn find_by_path(&self, path: &str) -> Option<Entity> {
+        let mut names = path.split('/').filter(|name| !name.is_empty());
+        let roots: Vec<Entity> =
+            self.named(names.next()?).filter(|e| self.internal_world.get::<&Parent>(*e).is_err()).collect();
+        self.follow_path(roots, names)
+    }
+
+    /// Retrieves a descendant of `entity` by the path of names leading to it, separated by `/`, like `"hud/score"`.
+    /// The first name is the one of a child of `entity`.
+    pub fn find_by_path_from(&self, entity: Entity, path: &str) -> Option<Entity> {
+        self.follow_path(vec![entity], path.split('/').filter(|name| !name.is_empty()))
+    }
+
+    /// Label of `entity` to use in logs, made of its name if it has one and of its id
+    pub fn entity_label(&self, entity: Entity) -> String {
+        match self.internal_world.get::<&Name>(entity) {
+            Ok(name) => format!("'{}' ({:?})", name.as_str(), entity),
+            Err(_) => format!("{:?}", entity),
+        }
+    }
+
+    fn follow_path<'a>(&self, mut candidates: Vec<Entity>, names: impl Iterator<Item = &'a str>) -> Option<Entity> {
+        for name in names {
+            // Several entities can share a name, so all the matching branches are followed
+            candidates = self
+                .named(name)
+                .filter(|e| self.internal_world.get::<&Parent>(*e).is_ok_and(|parent| candidates.contains(&parent.0)))
+                .collect();
+        }
+        candidates.first().copied()
+    }
+
+    /// Indexed entities named `name`, without the ones whose name was replaced in place since
+    fn named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = Entity> + 'a {
+        self.names
+            .named(name)
+            .iter()
+            .filter(move |e| self.internal_world.get::<&Name>(**e).is_ok_and(|n| n.as_str() == name))
+            .copied()
+    }
+
+    /// Indexes again the names and tags that were replaced in place, through a mutable query
+    pub(crate) fn reindex_names(&mut self) {
+        let outdated: Vec<Entity> = self
+            .names
+            .indexed_entities()
+            .filter(|e| {
+                let name = self.internal_world.get::<&Name>(**e).ok();
+                let tags = self.internal_world.get::<&Tags>(**e).ok();
+                !self.names.is_indexed(**e, name.as_deref(), tags.as_deref())
+            })
+            .copied()
+            .collect();
+        for entity in outdated {
+            self.unindex_names(entity);
+            self.index_names(entity);
+        }
+    }
+
+    fn index_names(&mut self, entity: Entity) {
+        let name = self.internal_world.get::<&Name>(entity).ok();
+        let tags = self.internal_world.get::<&Tags>(entity).ok();
+        self.names.insert(entity, name.as_deref(), tags.as_deref());
+    }
+
+    fn unindex_names(&mut self, entity: Entity) {
+        self.names.remove(entity);
+    }
+
+    /// Called by the hierarchy system once the [`Children`] match the [`Parent`] components
+    pub(crate) fn hierarchy_updated(&mut self) {
+        self.hierarchy_outdated = false;
+    }
+
+    /// Removes the existing `roots` from the `Children` of their parents and returns them along with all their
+    /// descendants
+    pub(crate) fn detach_subtrees(&mut self, roots: &[Entity]) -> Vec<Entity> {
+        let world = &mut self.internal_world;
+        let mut subtree = Vec::with_capacity(roots.len());
+        let mut visited = HashSet::with_capacity(roots.len());
+        let mut detached: HashMap<Entity, HashSet<Entity>> = HashMap::new();
+        for root in roots.iter().copied() {
+            if world.contains(root) && visited.insert(root) {
+                if let Ok(parent) = world.get::<&Parent>(root) {
+                    detached.entry(parent.0).or_default().insert(root);
+                }
+                subtree.push(root);
+            }
+        }
+        for (parent, roots) in detached {
+            if let Ok(mut children) = world.get::<&mut Children>(parent) {
+                children.0.retain(|child| !roots.contains(child));
+            }
+        }
+
+        // `Children` can be outdated until the hierarchy system runs, so `Parent` links are followed too
+        let mut children_by_parent: HashMap<Entity, Vec<Entity>> = HashMap::new();
+        if self.hierarchy_outdated {
+            for (child, parent) in world.query::<&Parent>().iter() {
+                children_by_parent.entry(parent.0).or_default().push(child);
+            }
+        }
+        let mut index = 0;
+        while index < subtree.len() {
+            let current = subtree[index];
+            let listed = world.get::<&Children>(current).map(|children| children.0.clone()).unwrap_or_default();
+            for child in listed.into_iter().chain(children_by_parent.remove(&current).unwrap_or_default()) {
+                if world.contains(child) && visited.insert(child) {
+                    subtree.push(child);
+                }
+            }
+            index += 1;
+        }
+        subtree
+    }
+
+    fn component_types(&self, entity: Entity) -> Vec<TypeId> {
+        self.internal_world.entity(entity).map(|e| e.component_types().collect()).unwrap_or_default()
+    }
 }
 
 #[derive(Default)]
@@ -236,14 +558,46 @@ impl World for SubWorld {
 
     fn clear(&mut self) {
         self.internal_world.clear();
+        self.changes.clear();
+        self.names.clear();
+        self.hierarchy_outdated = false;
     }
 
     fn push(&mut self, components: impl DynamicBundle) -> Entity {
-        self.internal_world.spawn(components)
+        let (parented, named) =
+            components.with_ids(|ids| (ids.contains(&TypeId::of::<Parent>()), ids.iter().any(NameIndex::indexes)));
+        self.hierarchy_outdated |= parented;
+        let entity = self.internal_world.spawn(components);
+        if named {
+            self.index_names(entity);
+        }
+        for type_id in self.internal_world.entity(entity).expect("Entity was just spawned").component_types() {
+            self.changes.record_inserted(type_id, entity);
+        }
+        entity
     }
 
     fn remove(&mut self, entity: Entity) -> Result<(), NoSuchEntity> {
-        self.internal_world.despawn(entity)
+        for type_id in self.internal_world.entity(entity)?.component_types() {
+            self.changes.record_removed(type_id, entity);
+        }
+        self.unindex_names(entity);
+        self.internal_world.despawn(entity)?;
+        Ok(())
+    }
+
+    fn remove_recursive(&mut self, entity: Entity) -> Result<(), NoSuchEntity> {
+        if !self.contains(entity) {
+            return Err(NoSuchEntity);
+        }
+        self.remove_all_recursive(&[entity]);
+        Ok(())
+    }
+
+    fn remove_all_recursive(&mut self, entities: &[Entity]) {
+        self.detach_subtrees(entities).drain(..).for_each(|e| {
+            let _r = self.remove(e);
+        });
     }
 
     fn add_components(
@@ -251,11 +605,37 @@ impl World for SubWorld {
         entity: Entity,
         components: impl DynamicBundle,
     ) -> Result<(), NoSuchEntity> {
-        self.internal_world.insert(entity, components)
+        if !self.internal_world.contains(entity) {
+            return Err(NoSuchEntity);
+        }
+        let changes = &mut self.changes;
+        let (parented, renamed) = components.with_ids(|ids| {
+            ids.iter().for_each(|type_id| changes.record_inserted(*type_id, entity));
+            (ids.contains(&TypeId::of::<Parent>()), ids.iter().any(NameIndex::indexes))
+        });
+        self.hierarchy_outdated |= parented;
+        if renamed {
+            self.unindex_names(entity);
+        }
+        self.internal_world.insert(entity, components)?;
+        if renamed {
+            self.index_names(entity);
+        }
+        Ok(())
     }
 
     fn remove_component<T: Component>(&mut self, entity: Entity) -> Result<T, ComponentError> {
-        self.internal_world.remove_one::<T>(entity)
+        let renamed = NameIndex::indexes(&TypeId::of::<T>()) && self.internal_world.contains(entity);
+        if renamed {
+            self.unindex_names(entity);
+        }
+        let component = self.internal_world.remove_one::<T>(entity);
+        if renamed {
+            self.index_names(entity);
+        }
+        let component = component?;
+        self.changes.record_removed(TypeId::of::<T>(), entity);
+        Ok(component)
     }
 
     fn query<Q: Query>(&self) -> QueryBorrow<'_, Q> {
@@ -373,6 +753,24 @@ impl Resources {
             .expect("The engine is missing the mandatory scene controller resource")
     }
 
+    /// retrieves the profiler from the resources
+    pub fn profiler(&self) -> AtomicRefMut<Profiler> {
+        self.get_resource_mut::<Profiler>()
+            .expect("The engine is missing the mandatory profiler resource")
+    }
+
+    /// retrieves the commands from the resources
+    pub fn commands(&self) -> AtomicRefMut<Commands> {
+        self.get_resource_mut::<Commands>()
+            .expect("The engine is missing the mandatory commands resource")
+    }
+
+    /// retrieves the observers from the resources
+    pub fn observers(&self) -> AtomicRefMut<Observers> {
+        self.get_resource_mut::<Observers>()
+            .expect("The engine is missing the mandatory observers resource")
+    }
+
     /// retrieves the font_atlas from the resources.
     pub(crate) fn font_atlas(&self) -> AtomicRefMut<FontAtlas> {
         self.get_resource_mut::<FontAtlas>()
@@ -384,6 +782,14 @@ impl Resources {
         self.get_resource_mut::<FocusManager>()
             .expect("The engine is missing the mandatory focus manager resource")
     }
+
+    /// Requests the application to quit at the end of the current frame.
+    /// The current scene will be stopped, and the rendering and audio threads will be shut down before exiting.
+    pub fn quit(&self) {
+        self.get_resource_mut::<GlobalStorage>()
+            .expect("The engine is missing the mandatory global storage resource")
+            .quit_requested = true;
+    }
 }
 
 #[derive(Default)]
@@ -465,6 +871,102 @@ impl AtomicResourceCell {
 mod tests {
     use super::*;
 
+    #[test]
+    fn remove_recursive_test() {
+        let mut world = GameData::default();
+        let root = world.push((1,));
+        let child = world.push((2, Parent(root)));
+        let _r = world.add_components(root, (Children(vec![child]),));
+        // Not yet listed in the `Children` of its parent
+        let grand_child = world.push((3, Parent(child)));
+        let sibling = world.push((4, Parent(root)));
+        let leaf = world.push((5, Parent(sibling)));
+        let _r = world.add_components(sibling, (Children(vec![leaf]),));
+        let other = world.push((6,));
+
+        assert!(world.remove_recursive(child).is_ok());
+        assert!(!world.contains(child));
+        assert!(!world.contains(grand_child));
+        assert!(world.entry::<&Children>(root).unwrap().get().unwrap().0.is_empty());
+
+        assert!(world.remove_recursive(root).is_ok());
+        assert_eq!(HashSet::from([other]), world.entities());
+        assert!(world.remove_recursive(root).is_err());
+    }
+
+    #[test]
+    fn remove_all_recursive_test() {
+        let mut world = GameData::default();
+        let root = world.push((1,));
+        let first = world.push((2, Parent(root)));
+        let second = world.push((3, Parent(root)));
+        let kept = world.push((4, Parent(root)));
+        let grand_child = world.push((5, Parent(first)));
+        let _r = world.add_components(root, (Children(vec![first, second, kept]),));
+        let _r = world.add_components(first, (Children(vec![grand_child]),));
+        world.subworld.hierarchy_updated();
+        // Not yet listed in the `Children` of its parent
+        let late_child = world.push((6, Parent(second)));
+
+        world.remove_all_recursive(&[first, second, first]);
+        assert_eq!(HashSet::from([root, kept]), world.entities());
+        assert_eq!(vec![kept], world.entry::<&Children>(root).unwrap().get().unwrap().0);
+        assert!(!world.contains(late_child));
+    }
+
+    #[test]
+    fn names_and_tags_index_test() {
+        let mut world = GameData::default();
+        let hud = world.push((Name::new("hud"), Tags::new(["ui"])));
+        let score = world.push((Name::new("score"), Parent(hud), Tags::new(["ui", "text"])));
+        let player = world.push((Name::new("player"),));
+        // Same name, but not under the root `hud`
+        let _other_score = world.push((Name::new("score"), Parent(player)));
+
+        assert_eq!(Some(hud), world.find_by_name("hud"));
+        assert_eq!(2, world.find_all_by_name("score").len());
+        assert_eq!(vec![hud, score], world.find_all_with_tag("ui"));
+        assert_eq!(Some(score), world.find_by_path("hud/score"));
+        assert_eq!(Some(score), world.find_by_path_from(hud, "score"));
+        assert_eq!(None, world.find_by_path("score"));
+        assert_eq!(format!("'player' ({:?})", player), world.entity_label(player));
+
+        let _r = world.add_components(player, (Name::new("hero"), Tags::new(["ui"])));
+        assert_eq!(None, world.find_by_name("player"));
+        assert_eq!(Some(player), world.find_by_name("hero"));
+        assert_eq!(3, world.find_all_with_tag("ui").len());
+
+        let _r = world.remove_component::<Tags>(score);
+        assert_eq!(vec![hud, player], world.find_all_with_tag("ui"));
+        let _r = world.remove_recursive(hud);
+        assert_eq!(None, world.find_by_path("hud/score"));
+        assert_eq!(1, world.find_all_by_name("score").len());
+
+        world.clear();
+        assert_eq!(None, world.find_by_name("hero"));
+    }
+
+    #[test]
+    fn names_and_tags_replaced_in_place_test() {
+        let mut world = GameData::default();
+        let hud = world.push((Name::new("hud"), Tags::new(["ui"])));
+        for (_, (name, tags)) in world.query_mut::<(&mut Name, &mut Tags)>() {
+            *name = Name::new("menu");
+            *tags = Tags::new(["debug"]);
+        }
+        assert_eq!(None, world.find_by_name("hud"));
+        assert!(world.find_all_with_tag("ui").is_empty());
+
+        world.subworld.reindex_names();
+        assert_eq!(Some(hud), world.find_by_name("menu"));
+        assert_eq!(Some(hud), world.find_by_path("menu"));
+        assert_eq!(vec![hud], world.find_all_with_tag("debug"));
+
+        let _r = world.remove(hud);
+        assert_eq!(None, world.find_by_name("menu"));
+        assert!(world.subworld.names.indexed_entities().next().is_none());
+    }
+
     #[test]
     fn simple_read_write_test() {
         struct TestOne {
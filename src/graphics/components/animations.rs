@@ -6,9 +6,12 @@ use std::{
     time::Duration,
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{graphics::components::color::Color, utils::maths::Vector};
 use crate::graphics::components::animations::AnimationStatus::{ForceStopped, Stopped};
 
+#[derive(Serialize, Deserialize)]
 pub struct Animations {
     animations: HashMap<String, Animation>,
 }
@@ -116,7 +119,7 @@ impl Animations {
     }
 }
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Eq, PartialEq, Debug, Serialize, Deserialize)]
 pub(crate) enum AnimationStatus {
     ForceStopped,
     Stopped,
@@ -125,6 +128,7 @@ pub(crate) enum AnimationStatus {
     Stopping,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Animation {
     pub(crate) _duration: Duration,
     pub(crate) modifiers: Vec<AnimationModifier>,
@@ -184,6 +188,7 @@ impl Animation {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct AnimationModifier {
     pub(crate) number_of_keyframes: usize,
     pub(crate) current_keyframe: usize,
@@ -309,7 +314,7 @@ impl AnimationModifier {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AnimationModifierType {
     TransformModifier {
         vector: Option<Vector>,
@@ -330,6 +335,7 @@ pub enum AnimationModifierType {
     Blink,
 }
 
+#[derive(Serialize, Deserialize)]
 pub(crate) enum ComputedKeyframeModifier {
     TransformModifier { vector: Option<Vector>, scale: Option<f32>, rotation: Option<f32> },
     Color { r: i16, g: i16, b: i16, a: f32 },
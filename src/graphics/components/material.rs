@@ -2,6 +2,7 @@ use std::fmt::{Display, Formatter};
 use std::path::Path;
 
 use image::{DynamicImage, GenericImage, ImageBuffer, ImageFormat};
+use serde::{Deserialize, Serialize};
 
 
 use crate::{
@@ -11,7 +12,7 @@ use crate::{
 
 
 /// Component used by the 2D Renderer to know which material to use when graphics a renderable object.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum Material {
     /// Fill with a color
     Diffuse(Color),
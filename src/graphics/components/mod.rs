@@ -1,6 +1,7 @@
 //! Contains all the components provided by `Scion`
 
 pub use shapes::{square::Square, triangle::Triangle};
+use serde::{Deserialize, Serialize};
 
 pub mod animations;
 pub mod color;
@@ -10,6 +11,13 @@ pub mod tiles;
 pub mod ui;
 
 /// Struct to add to any entity to 'hide' it during renderig
+#[derive(Serialize, Deserialize)]
 pub struct Hide;
 
 pub(crate) struct HidePropagated;
+
+/// Added to the entities hidden by a scene pushed on top of the stack without rendering below
+pub(crate) struct HiddenByScene;
+
+/// Opacity applied to an entity while a scene transition is running
+pub(crate) struct TransitionOpacity(pub(crate) f32);
@@ -1,5 +1,6 @@
 use std::ops::Range;
 
+use serde::{Deserialize, Serialize};
 use wgpu::{PrimitiveTopology, util::BufferInitDescriptor};
 
 use crate::{
@@ -16,11 +17,12 @@ use crate::utils::maths::Vector;
 const INDICES: &[u16] = &[0, 1, 3, 3, 1, 2];
 
 /// Renderable Sprite.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Sprite {
     /// Desired tile to render for this material.
     tile_number: usize,
     /// Current computed content for vertex
+    #[serde(skip)]
     contents: Option<[TexturedGlVertexWithLayer; 4]>,
     /// Flag to keep track of changed tile number
     dirty: bool,
@@ -2,11 +2,12 @@ use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use log::error;
+use serde::{Deserialize, Serialize};
 
 use crate::graphics::components::tiles::atlas::data::{TileConfig, TilesetAtlas};
 use crate::utils::file::read_file;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 /// Struct representing a tileset definition.
 pub struct Tileset {
     pub(crate) name: String,
@@ -1,5 +1,6 @@
 use std::ops::Range;
 
+use serde::{Deserialize, Serialize};
 use wgpu::{PrimitiveTopology, util::BufferInitDescriptor};
 
 use crate::{
@@ -15,6 +16,10 @@ use crate::core::resources::asset_manager::AssetRef;
 use crate::core::world::Resources;
 
 /// A component representing a Text in the UI.
+///
+/// The synchronisation function given to [`UiText::sync_value`] is not part of the world snapshots, unlike the
+/// game state key given to [`UiText::sync_state`].
+#[derive(Serialize, Deserialize)]
 pub struct UiText {
     text: String,
     font_ref: AssetRef<Font>,
@@ -24,14 +29,22 @@ pub struct UiText {
     font_color: Option<Color>,
     /// Optional text settings when used in buttons
     padding: Padding,
+    /// The characters are not part of the snapshots, so a restored text is always generated again
+    #[serde(skip, default = "restored_dirty")]
     pub(crate) dirty: bool,
-    pub(crate) sync_fn: Option<fn(&mut Resources) -> String>
+    #[serde(skip)]
+    pub(crate) sync_fn: Option<fn(&mut Resources) -> String>,
+    #[serde(default)]
+    pub(crate) sync_key: Option<String>,
+    /// Game state version of the last synchronisation with `sync_key`
+    #[serde(skip)]
+    pub(crate) synced_version: Option<u64>,
 }
 
 impl UiText {
     /// Creates a new `UiText` with `text` as default content and `font`
     pub fn new(text: String, font_ref: AssetRef<Font>) -> Self {
-        Self { text, font_ref, dirty: true, font_size: 10, font_color: None, sync_fn: None, padding: Padding::default() }
+        Self { text, font_ref, dirty: true, font_size: 10, font_color: None, sync_fn: None, sync_key: None, synced_version: None, padding: Padding::default() }
     }
 
     /// provide a fn that will automatically synchronize the text
@@ -42,6 +55,14 @@ impl UiText {
         self
     }
 
+    /// synchronizes the text with the value of `key` in the [`crate::core::state::GameState`].
+    /// The text is only refreshed when the value changes.
+    pub fn sync_state(mut self, key: &str) -> Self {
+        self.sync_key = Some(key.to_string());
+        self.synced_version = None;
+        self
+    }
+
     /// retrieves the content of this `UiText`
     pub fn text(&self) -> &String {
         &self.text
@@ -90,6 +111,10 @@ impl UiText {
 
 }
 
+fn restored_dirty() -> bool {
+    true
+}
+
 /// `UiTextImage` is an internal component used to keep track of the character in case of a
 /// bitmap font
 #[derive(Debug)]
@@ -3,6 +3,7 @@ use std::time::SystemTime;
 
 use hecs::Entity;
 
+use crate::core::components::maths::camera::Camera;
 use crate::graphics::components::{Square, Triangle};
 use crate::graphics::components::shapes::line::Line;
 use crate::graphics::components::shapes::polygon::Polygon;
@@ -18,10 +19,23 @@ use crate::graphics::rendering::scion2d::utils::prepare_material_updates;
 use crate::graphics::rendering::scion2d::utils::prepare_transform_updates;
 use crate::utils::file::FileReaderError;
 
+/// Values, other than the transform, that the transform uniform of an entity was last built with
+#[derive(PartialEq)]
+pub(crate) struct UniformInputs {
+    pub(crate) pivot_offset: (f32, f32),
+    pub(crate) is_ui_component: bool,
+    pub(crate) opacity: f32,
+}
+
 #[derive(Default)]
 pub(crate) struct Scion2DPreRenderer {
     textures_timestamps: HashMap<String, SystemTime>,
-    transform_uniform: HashSet<Entity>,
+    /// Entities having a transform uniform, with the values it was built with
+    pub(crate) transform_uniform: HashMap<Entity, UniformInputs>,
+    /// Change tick of the previous transform uniforms update
+    pub(crate) transform_tick: u64,
+    /// Camera used by the previous transform uniforms update
+    pub(crate) camera: Option<Camera>,
     vertex_buffer: HashSet<Entity>,
     indexes_buffer: HashSet<Entity>,
 }
@@ -92,7 +106,7 @@ impl Scion2DPreRenderer {
     fn clean_buffers(&mut self, data: &mut GameData) {
         self.vertex_buffer.retain(|&k| data.contains(k));
         self.indexes_buffer.retain(|&k| data.contains(k));
-        self.transform_uniform.retain(|&k| data.contains(k));
+        self.transform_uniform.retain(|&k, _| data.contains(k));
         // TODO transfer a clean buffer update to the rendering thread
     }
 }
\ No newline at end of file
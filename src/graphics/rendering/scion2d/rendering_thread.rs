@@ -1,4 +1,7 @@
-use std::sync::mpsc::{Receiver};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use log::{info};
 use crate::graphics::rendering::{RendererEvent, RenderingInfos, RenderingUpdate};
 use crate::graphics::rendering::scion2d::window_rendering_manager::ScionWindowRenderingManager;
@@ -6,46 +9,65 @@ use crate::graphics::rendering::scion2d::window_rendering_manager::ScionWindowRe
 pub(crate) struct ScionRenderingThread {
     pub(crate) window_rendering_manager: Option<ScionWindowRenderingManager>,
     pub(crate) render_receiver: Receiver<(Vec<RendererEvent>, Vec<RenderingUpdate>, Vec<RenderingInfos>)>,
+    /// Sends the duration of the rendering phases back to the game loop, for the profiler
+    pub(crate) timings_sender: Sender<(&'static str, Instant, Duration)>,
+    /// Whether the profiler is enabled, set by the game loop
+    pub(crate) profiling: Arc<AtomicBool>,
 }
 
 impl ScionRenderingThread{
     pub(crate) fn run(mut self) {
         info!("Initializing rendering thread");
         let mut update_accumulator: Vec<RenderingUpdate> = Vec::new();
-        loop {
-            if let Ok((mut events, mut updates, rendering_infos)) = self.render_receiver.recv() {
-                events.drain(0..events.len()).for_each(|event|{
-                    match event {
-                        RendererEvent::ForceRedraw => {
-                            // TODO
-                        }
-                        RendererEvent::Resize(physical_size, scale_factor) => {
-                            self.window_rendering_manager.as_mut().unwrap().resize(physical_size, scale_factor);
-                        }
+        while let Ok((mut events, mut updates, rendering_infos)) = self.render_receiver.recv() {
+            events.drain(0..events.len()).for_each(|event|{
+                match event {
+                    RendererEvent::ForceRedraw => {
+                        // TODO
+                    }
+                    RendererEvent::Resize(physical_size, scale_factor) => {
+                        self.window_rendering_manager.as_mut().unwrap().resize(physical_size, scale_factor);
                     }
-                });
-
-                if !updates.is_empty(){
-                    update_accumulator.append(&mut updates);
                 }
+            });
+
+            if !updates.is_empty(){
+                update_accumulator.append(&mut updates);
+            }
 
-                if !update_accumulator.is_empty() || !rendering_infos.is_empty() {
-                    if self.window_rendering_manager.as_ref().unwrap().should_render(){
-                        self.window_rendering_manager.as_mut().unwrap().update(&mut update_accumulator);
-                        match self.window_rendering_manager.as_mut().unwrap().render(rendering_infos) {
-                            Ok(_) => {}
-                            Err(e) => log::error!("{:?}", e),
-                        }
+            if !update_accumulator.is_empty() || !rendering_infos.is_empty() {
+                if self.window_rendering_manager.as_ref().unwrap().should_render(){
+                    let start = Instant::now();
+                    self.window_rendering_manager.as_mut().unwrap().update(&mut update_accumulator);
+                    self.send_timing("renderer_update", start);
+                    let start = Instant::now();
+                    match self.window_rendering_manager.as_mut().unwrap().render(rendering_infos) {
+                        Ok(_) => {}
+                        Err(e) => log::error!("{:?}", e),
                     }
+                    self.send_timing("renderer_render", start);
                 }
             }
         }
+        info!("Rendering thread stopped");
+    }
+
+    /// Sends the duration of the rendering phase `name` started at `start`, only if the profiler is enabled
+    fn send_timing(&self, name: &'static str, start: Instant) {
+        if self.profiling.load(Ordering::Relaxed) {
+            let _r = self.timings_sender.send((name, start, start.elapsed()));
+        }
     }
 
-    pub fn new(window_rendering_manager: Option<ScionWindowRenderingManager>, render_receiver: Receiver<(Vec<RendererEvent>, Vec<RenderingUpdate>, Vec<RenderingInfos>)>) -> Self{
+    pub fn new(window_rendering_manager: Option<ScionWindowRenderingManager>,
+               render_receiver: Receiver<(Vec<RendererEvent>, Vec<RenderingUpdate>, Vec<RenderingInfos>)>,
+               timings_sender: Sender<(&'static str, Instant, Duration)>,
+               profiling: Arc<AtomicBool>) -> Self{
         Self{
             window_rendering_manager,
             render_receiver,
+            timings_sender,
+            profiling,
         }
     }
 }
@@ -1,6 +1,6 @@
 use hecs::Component;
 
-use crate::graphics::components::{Hide, HidePropagated};
+use crate::graphics::components::{Hide, HiddenByScene, HidePropagated};
 use crate::graphics::components::material::Material;
 use crate::core::components::maths::transform::Transform;
 use crate::graphics::components::tiles::sprite::Sprite;
@@ -18,6 +18,7 @@ pub(crate) fn pre_render_component<T: Component + Renderable2D>(
         .without::<&Tile>()
         .without::<&Hide>()
         .without::<&HidePropagated>()
+        .without::<&HiddenByScene>()
         .iter()
     {
         let path = match material {
@@ -45,6 +46,7 @@ pub(crate) fn pre_render_tilemaps(data: &mut GameData) -> Vec<RenderingInfos> {
     for (entity, (_, material, transform)) in data
         .query::<(&mut Tilemap, &Material, &Transform)>()
         .without::<(&Hide, &HidePropagated)>()
+        .without::<&HiddenByScene>()
         .iter()
     {
         let tiles_nb = tiles
@@ -76,6 +78,7 @@ pub(crate) fn pre_render_ui_component<T: Component + Renderable2D + RenderableUi
     data.query::<(&mut T, &Transform, Option<&Material>)>()
         .without::<&Hide>()
         .without::<&HidePropagated>()
+        .without::<&HiddenByScene>()
         .iter()
     {
         let path = if material.is_some() {
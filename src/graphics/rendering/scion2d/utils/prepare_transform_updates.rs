@@ -1,9 +1,12 @@
-use hecs::Component;
+use std::collections::HashSet;
 
-use crate::graphics::components::{Square, Triangle};
+use hecs::{Component, Entity};
+
+use crate::graphics::components::{Square, TransitionOpacity, Triangle};
 use crate::graphics::components::material::Material;
 use crate::core::components::maths::camera::Camera;
-use crate::core::components::maths::transform::Transform;
+use crate::core::components::maths::transform::{Interpolated, Transform};
+use crate::core::resources::time::Time;
 use crate::graphics::components::shapes::line::Line;
 use crate::graphics::components::shapes::polygon::Polygon;
 use crate::graphics::components::shapes::rectangle::Rectangle;
@@ -15,46 +18,88 @@ use crate::graphics::components::ui::UiComponent;
 use crate::core::world::{GameData, World};
 use crate::graphics::rendering::{Renderable2D, RenderingUpdate};
 use crate::graphics::rendering::shaders::gl_representations::{GlUniform, UniformData};
-use crate::graphics::rendering::scion2d::pre_renderer::Scion2DPreRenderer;
+use crate::graphics::rendering::scion2d::pre_renderer::{Scion2DPreRenderer, UniformInputs};
 
+/// Builds the transform uniforms that need to be sent to the renderer : the ones of the new entities, of the entities
+/// whose transform changed since the previous update, or whose camera, pivot, opacity or interpolation changed.
 pub(crate) fn call(renderer: &mut Scion2DPreRenderer, data: &mut GameData) -> Vec<RenderingUpdate> {
+    let (camera_entity, camera) = {
+        let mut camera = (None, Camera::new(1.0, 1.0), Transform::default());
+        for (entity, (cam, tra)) in data.query::<(&Camera, &Transform)>().iter() {
+            camera = (Some(entity), cam.clone(), *tra);
+        }
+        (camera.0, (camera.1, camera.2))
+    };
+    let changed = changed_transforms(renderer, data);
+    let camera_changed =
+        renderer.camera.as_ref() != Some(&camera.0) || camera_entity.is_some_and(|entity| changed.contains(&entity));
+    renderer.camera = Some(camera.0.clone());
+    let context = UpdateContext { camera: (&camera.0, &camera.1), camera_changed, changed: &changed };
+
     let mut updates = vec![];
-    updates.append(&mut update_transforms_for_type::<Triangle>(renderer, data));
-    updates.append(&mut update_transforms_for_type::<Square>(renderer, data));
-    updates.append(&mut update_transforms_for_type::<Rectangle>(renderer, data));
-    updates.append(&mut update_transforms_for_type::<Sprite>(renderer, data));
-    updates.append(&mut update_transforms_for_type::<Line>(renderer, data));
-    updates.append(&mut update_transforms_for_type::<Polygon>(renderer, data));
-    updates.append(&mut update_transforms_for_type::<UiImage>(renderer, data));
-    updates.append(&mut update_transforms_for_type::<UiTextImage>(renderer, data));
-    updates.append(&mut update_transforms_for_type::<Tilemap>(renderer, data));
+    updates.append(&mut update_transforms_for_type::<Triangle>(renderer, data, &context));
+    updates.append(&mut update_transforms_for_type::<Square>(renderer, data, &context));
+    updates.append(&mut update_transforms_for_type::<Rectangle>(renderer, data, &context));
+    updates.append(&mut update_transforms_for_type::<Sprite>(renderer, data, &context));
+    updates.append(&mut update_transforms_for_type::<Line>(renderer, data, &context));
+    updates.append(&mut update_transforms_for_type::<Polygon>(renderer, data, &context));
+    updates.append(&mut update_transforms_for_type::<UiImage>(renderer, data, &context));
+    updates.append(&mut update_transforms_for_type::<UiTextImage>(renderer, data, &context));
+    updates.append(&mut update_transforms_for_type::<Tilemap>(renderer, data, &context));
     updates
 }
 
+struct UpdateContext<'a> {
+    camera: (&'a Camera, &'a Transform),
+    camera_changed: bool,
+    changed: &'a HashSet<Entity>,
+}
+
+/// Entities whose transform changed since the previous update. The transforms mutated after the
+/// `dirty_transform_system` of this frame are not reported yet, but are still flagged as dirty.
+fn changed_transforms(renderer: &mut Scion2DPreRenderer, data: &GameData) -> HashSet<Entity> {
+    let tick = data.changes().begin_system();
+    let mut changed: HashSet<Entity> = data.changes().changed::<Transform>(renderer.transform_tick).into_iter().collect();
+    changed.extend(data.query::<&Transform>().iter().filter(|(_, t)| t.dirty || t.dirty_child).map(|(e, _)| e));
+    data.changes().end_system();
+    renderer.transform_tick = tick;
+    changed
+}
+
 fn update_transforms_for_type<T: Component + Renderable2D>(
-    _renderer: &mut Scion2DPreRenderer,
-    data: &mut GameData) -> Vec<RenderingUpdate> {
+    renderer: &mut Scion2DPreRenderer,
+    data: &mut GameData,
+    context: &UpdateContext) -> Vec<RenderingUpdate> {
     let mut updates = vec![];
-    let camera1 = {
-        let mut t = Transform::default();
-        let mut c = Camera::new(1.0, 1.0);
-
-        for (_, (cam, tra)) in data.query::<(&Camera, &Transform)>().iter() {
-            c = cam.clone();
-            t = *tra;
+    let camera = context.camera;
+    let alpha = data.get_resource::<Time>().map_or(1., |time| time.interpolation_alpha());
+    for (entity, (transform, optional_ui_component, renderable, optional_material, optional_interpolated, optional_opacity)) in
+    data.query::<(&Transform, Option<&UiComponent>, &T, Option<&Material>, Option<&Interpolated>, Option<&TransitionOpacity>)>().iter() {
+        let pivot_offset = renderable.get_pivot_offset(optional_material);
+        let inputs = UniformInputs {
+            pivot_offset: (pivot_offset.x, pivot_offset.y),
+            is_ui_component: optional_ui_component.is_some(),
+            opacity: optional_opacity.map_or(1., |opacity| opacity.0),
+        };
+        let interpolating = optional_interpolated.is_some_and(|interpolated| interpolated.previous.is_some());
+        if !context.camera_changed
+            && !interpolating
+            && !context.changed.contains(&entity)
+            && renderer.transform_uniform.get(&entity) == Some(&inputs)
+        {
+            continue;
         }
-        (c, t)
-    };
-    let camera = (&camera1.0, &camera1.1);
-    for (entity, (transform, optional_ui_component, renderable, optional_material)) in
-    data.query::<(&Transform, Option<&UiComponent>, &T, Option<&Material>)>().iter() {
-        // TODO : update only if needed ?
+        let interpolated_transform = optional_interpolated
+            .and_then(|interpolated| interpolated.previous)
+            .map(|(previous_translation, previous_angle)| transform.interpolated_from(&previous_translation, previous_angle, alpha));
         let uniform = GlUniform::from(UniformData {
-            transform,
+            transform: interpolated_transform.as_ref().unwrap_or(transform),
             camera,
-            is_ui_component: optional_ui_component.is_some(),
-            pivot_offset: renderable.get_pivot_offset(optional_material),
+            is_ui_component: inputs.is_ui_component,
+            pivot_offset,
+            opacity: inputs.opacity,
         });
+        renderer.transform_uniform.insert(entity, inputs);
         updates.push(RenderingUpdate::TransformUniform {
             entity,
             uniform,
@@ -63,3 +108,36 @@ fn update_transforms_for_type<T: Component + Renderable2D>(
     updates
 }
 
+
+#[cfg(test)]
+mod tests {
+    use crate::core::systems::parent_transform_system::{dirty_child_system, dirty_transform_system};
+
+    use super::*;
+
+    #[test]
+    fn only_changed_uniforms_are_updated_test() {
+        let mut data = GameData::default();
+        let mut renderer = Scion2DPreRenderer::default();
+        data.push((Camera::new(100., 100.), Transform::default()));
+        let moving = data.push((Square::new(1., None), Transform::from_xy(1., 1.)));
+        let _still = data.push((Square::new(1., None), Transform::from_xy(2., 2.)));
+        let faded = data.push((Square::new(1., None), Transform::from_xy(3., 3.)));
+        dirty_child_system(&mut data);
+
+        assert_eq!(3, call(&mut renderer, &mut data).len());
+        assert!(call(&mut renderer, &mut data).is_empty());
+
+        data.entry_mut::<&mut Transform>(moving).unwrap().append_x(1.);
+        let _r = data.add_components(faded, (TransitionOpacity(0.5),));
+        dirty_transform_system(&mut data);
+        assert_eq!(2, call(&mut renderer, &mut data).len());
+        assert!(call(&mut renderer, &mut data).is_empty());
+
+        // Transforms mutated after the dirty_transform_system are updated too
+        for (_, (_, transform)) in data.query_mut::<(&Camera, &mut Transform)>() {
+            transform.append_y(5.);
+        }
+        assert_eq!(3, call(&mut renderer, &mut data).len());
+    }
+}
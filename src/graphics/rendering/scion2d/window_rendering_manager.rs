@@ -1,8 +1,10 @@
 use std::sync::Arc;
 
+use log::warn;
 use wgpu::{Limits, Surface, SurfaceConfiguration};
 use winit::{window::Window};
 
+use crate::config::frame_limiter_config::PresentMode;
 use crate::graphics::components::color::Color;
 use crate::graphics::rendering::{RenderingInfos, RenderingUpdate};
 use crate::graphics::rendering::scion2d::renderer::Scion2D;
@@ -19,7 +21,8 @@ pub(crate) struct ScionWindowRenderingManager {
 
 impl ScionWindowRenderingManager {
     pub(crate) async fn new(window: Arc<Window>,
-                            default_background : Option<Color>) -> Self {
+                            default_background : Option<Color>,
+                            present_mode: PresentMode) -> Self {
         let size = window.inner_size();
         let width = size.width.max(1);
         let height = size.height.max(1);
@@ -61,6 +64,13 @@ impl ScionWindowRenderingManager {
         let mut config = surface
             .get_default_config(&adapter, width, height)
             .unwrap();
+        let wanted_present_mode = wgpu::PresentMode::from(present_mode);
+        config.present_mode = if surface.get_capabilities(&adapter).present_modes.contains(&wanted_present_mode) {
+            wanted_present_mode
+        } else {
+            warn!("Present mode {:?} is not supported by the surface, using AutoVsync instead", present_mode);
+            wgpu::PresentMode::AutoVsync
+        };
 
         surface.configure(&device, &config);
 
@@ -166,12 +166,15 @@ impl From<(&Coordinates, &Coordinates, usize)> for TexturedGlVertexWithLayer {
 pub(crate) struct GlUniform {
     pub model_trans: [[f32; 4]; 4],
     pub camera_view: [[f32; 4]; 4],
+    /// Only the first value is used, the others are padding
+    pub opacity: [f32; 4],
 }
 
 impl GlUniform {
     pub(crate) fn replace_with(&mut self, other: GlUniform) {
         self.model_trans = other.model_trans;
         self.camera_view = other.camera_view;
+        self.opacity = other.opacity;
     }
 }
 
@@ -192,7 +195,8 @@ pub(crate) struct UniformData<'a> {
     pub transform: &'a Transform,
     pub camera: (&'a Camera, &'a Transform),
     pub is_ui_component: bool,
-    pub pivot_offset: Vector
+    pub pivot_offset: Vector,
+    pub opacity: f32,
 }
 
 impl From<UniformData<'_>> for GlUniform {
@@ -225,6 +229,7 @@ impl From<UniformData<'_>> for GlUniform {
         GlUniform {
             model_trans: create_glmat4(&mut model_trans),
             camera_view: create_glmat4(&mut camera_view),
+            opacity: [uniform_data.opacity, 0., 0., 0.],
         }
     }
 }
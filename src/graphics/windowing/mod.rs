@@ -2,6 +2,13 @@ use winit::event::WindowEvent;
 
 pub(crate) mod window_event_handler;
 
+/// Commands sent by the game loop to the main thread handling the window
+#[derive(Debug)]
+pub(crate) enum WindowCommand {
+    /// The game loop has been shut down, the window can be closed
+    Exit,
+}
+
 #[derive(Debug)]
 pub struct WindowingEvent {
     pub(crate) window_event: Option<WindowEvent>,
@@ -19,6 +19,10 @@ pub fn handle_window_event(runner: &mut ScionRunner) -> Vec<RendererEvent> {
             };
             if let Some(window_event) = event.window_event {
                 match window_event {
+                    WindowEvent::CloseRequested if runner.layer_machine.close_requested(&mut runner.game_data) => {
+                        info!("Window close has been requested, quitting");
+                        runner.game_data.quit();
+                    }
                     WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
                         update.push(RendererEvent::Resize(runner.window.as_ref().expect("Missing window").inner_size(), scale_factor));
                         for (_, camera) in runner.game_data.query_mut::<&mut Camera>() {
@@ -1,7 +1,9 @@
 use std::{
     env,
+    fs,
     fs::File,
-    io::Read,
+    io,
+    io::{Read, Write},
     path,
     path::{Path, PathBuf},
     time::SystemTime,
@@ -38,6 +40,61 @@ pub fn open_file(path: &Path) -> Result<File, FileReaderError> {
     }
 }
 
+/// Writes `bytes` to `path` through a temporary file renamed once fully written, so that `path` never contains
+/// partially written data, even if the application is stopped during the write. Missing parent directories are created.
+pub fn write_file_atomically(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(".tmp");
+    let temporary_path = PathBuf::from(temporary_path);
+    {
+        let mut file = File::create(&temporary_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    fs::rename(&temporary_path, path)
+}
+
+/// Per-user directory where the application `app_name` can store its data, like save games :
+/// `$XDG_DATA_HOME/app_name` (defaulting to `~/.local/share/app_name`) on Linux,
+/// `~/Library/Application Support/app_name` on macOS and `%APPDATA%\app_name` on Windows.
+/// Falls back to the [`app_base_path`] when the directory can't be determined.
+///
+/// The characters of `app_name` other than letters, digits, spaces, `-`, `_` and `.` are replaced by `_`, as well as
+/// a name made of dots only, so that the directory is always a direct child of the data directory.
+pub fn user_data_path(app_name: &str) -> PathBuf {
+    let app_name = directory_name(app_name);
+    let home = || env::var_os("HOME").filter(|home| !home.is_empty()).map(PathBuf::from);
+    let data_dir = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").filter(|dir| !dir.is_empty()).map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .or_else(|| home().map(|home| home.join(".local").join("share")))
+    };
+    match data_dir {
+        Some(dir) => dir.join(app_name),
+        None => app_base_path().path_buff.join(app_name),
+    }
+}
+
+fn directory_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == ' ' || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    if name.chars().all(|c| c == '.') {
+        "_".repeat(name.len().max(1))
+    } else {
+        name
+    }
+}
+
 /// This will give you the path to the executable (when in build mode) or to the root of the current project.
 pub fn app_base_path() -> PathBuilder {
     if let Some(manifest_dir) = env::var_os("CARGO_MANIFEST_DIR") {
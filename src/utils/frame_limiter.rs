@@ -1,117 +1,174 @@
 use std::time::{Duration, Instant};
 
-use serde::{Deserialize, Serialize};
-
-/// In order to reduce the cpu usage, the `FrameLimiter` will handle an
-/// ecs Lock if a frame used less time than expected.
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub(crate) enum FrameLimiterStrategy {
-    /// The `FrameLimiter` won't try to sleep and will launch the next ecs frame
-    /// immediately after the previous one.
-    Unlimited,
-    /// The `FrameLimiter` will compute the expected duration of a frame and
-    /// do a background sleep, locking the ecs, but not the rendering or window events.
-    /// usize is the maximum expected fps number
-    Sleep,
-}
+use log::debug;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
-pub(crate) struct FrameLimiterConfig {
-    strategy: FrameLimiterStrategy,
-    fps: Option<u32>,
+use crate::config::frame_limiter_config::{FrameLimiterConfig, FrameLimiterStrategy};
+
+/// `FixedTimestep` accumulates the elapsed time and tells how many fixed steps have to be executed to catch up with it.
+pub(crate) struct FixedTimestep {
+    fixed_duration: Duration,
+    max_steps: u32,
+    accumulator: Duration,
 }
 
-impl Default for FrameLimiterConfig {
-    fn default() -> Self {
-        Self {
-            strategy: FrameLimiterStrategy::Sleep,
-            fps: Some(60),
+impl FixedTimestep {
+    pub(crate) fn new(fixed_duration: Duration, max_steps: u32) -> Self {
+        Self { fixed_duration, max_steps, accumulator: Duration::from_secs(0) }
+    }
+
+    pub(crate) fn from_config(config: &FrameLimiterConfig) -> Self {
+        Self::new(rate_to_duration("fixed_update_rate", config.fixed_update_rate), config.max_fixed_updates_per_frame)
+    }
+
+    /// Adds `delta` to the accumulated time and returns the number of fixed steps to execute.
+    /// If more than `max_steps` are due, the late time is dropped to avoid spiraling down.
+    pub(crate) fn accumulate(&mut self, delta: Duration) -> u32 {
+        self.accumulator += delta;
+        let fixed_nanos = self.fixed_duration.as_nanos();
+        let due_steps = self.accumulator.as_nanos() / fixed_nanos;
+        if due_steps > self.max_steps as u128 {
+            debug!("Game loop is late by {} fixed steps, dropping {} of them", due_steps, due_steps - self.max_steps as u128);
+            self.accumulator = Duration::from_nanos((self.accumulator.as_nanos() % fixed_nanos) as u64);
+            self.max_steps
+        } else {
+            self.accumulator -= self.fixed_duration * due_steps as u32;
+            due_steps as u32
         }
     }
+
+    /// Ratio between the accumulated time not yet consumed by a fixed step and the fixed step duration
+    pub(crate) fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / self.fixed_duration.as_secs_f32()
+    }
+
+    pub(crate) fn fixed_duration(&self) -> Duration {
+        self.fixed_duration
+    }
+
+    fn remaining_before_next_step(&self) -> Duration {
+        self.fixed_duration.saturating_sub(self.accumulator)
+    }
 }
 
 pub(crate) struct FrameLimiter {
     strategy: FrameLimiterStrategy,
     target_render_duration: Duration,
-    target_fixed_duration: Duration,
-    pub(crate) min_tick_duration: Duration,
+    min_tick_duration: Duration,
     last_render_frame_start: Instant,
     last_tick_start: Instant,
-    last_fixed_tick_start: Instant,
+    last_fixed_measure: Instant,
+    fixed_timestep: FixedTimestep,
 }
 
 impl FrameLimiter {
-    pub fn new(config: FrameLimiterConfig) -> FrameLimiter {
-        let target_frame_duration = {
-            match config.strategy {
-                FrameLimiterStrategy::Unlimited => Duration::from_secs(0),
-                FrameLimiterStrategy::Sleep => {
-                    let fps = config.fps.unwrap_or(60);
-                    assert!(fps > 0, "FrameLimiter::config parameter `fps` is {}. This parameter must be greater than zero!", fps);
-                    Duration::from_secs(1)/ fps
-                }
+    pub fn new(config: &FrameLimiterConfig) -> FrameLimiter {
+        let (min_tick_duration, target_render_duration) = match config.strategy {
+            FrameLimiterStrategy::Unlimited => (Duration::from_secs(0), Duration::from_secs(0)),
+            FrameLimiterStrategy::Sleep => {
+                (rate_to_duration("update_rate", config.update_rate), rate_to_duration("render_rate", config.render_rate))
             }
         };
 
         Self {
-            strategy: config.strategy,
-            target_render_duration: target_frame_duration,
-            target_fixed_duration:  Duration::from_secs(1) / 60,
-            min_tick_duration:  Duration::from_secs(1) / 60,
+            strategy: config.strategy.clone(),
+            target_render_duration,
+            min_tick_duration,
             last_render_frame_start: Instant::now(),
-            last_fixed_tick_start: Instant::now(),
             last_tick_start: Instant::now(),
+            last_fixed_measure: Instant::now(),
+            fixed_timestep: FixedTimestep::from_config(config),
         }
     }
 
     pub fn render(&mut self) {
         self.last_render_frame_start = Instant::now();
     }
-    pub fn fixed_tick(&mut self) {
-        self.last_fixed_tick_start = Instant::now();
-    }
     pub fn tick(&mut self, instant: &Instant) {
-        self.last_tick_start = instant.clone();
+        self.last_tick_start = *instant;
     }
 
     pub fn render_unlocked(&mut self) -> bool {
         match self.strategy {
-            FrameLimiterStrategy::Unlimited => {
-                true
-            }
-            FrameLimiterStrategy::Sleep => {
-                let frame_start = self.last_render_frame_start;
-                let target_frame_duration = self.target_render_duration;
-                let elapsed = Instant::now() - frame_start;
-                if elapsed < target_frame_duration {
-                    false
-                } else {
-                    true
-                }
-            }
+            FrameLimiterStrategy::Unlimited => true,
+            FrameLimiterStrategy::Sleep => self.last_render_frame_start.elapsed() >= self.target_render_duration,
         }
     }
 
-    pub fn is_fixed_update(&mut self) -> bool {
-        let frame_start = self.last_fixed_tick_start;
-        let target_frame_duration = self.target_fixed_duration;
-        let elapsed = Instant::now() - frame_start;
-        if elapsed < target_frame_duration {
-            false
-        } else {
-            true
-        }
+    /// Returns the number of fixed updates to execute since the last call
+    pub fn fixed_updates(&mut self) -> u32 {
+        let now = Instant::now();
+        let steps = self.fixed_timestep.accumulate(now - self.last_fixed_measure);
+        self.last_fixed_measure = now;
+        steps
+    }
+
+    pub fn fixed_timestep(&self) -> &FixedTimestep {
+        &self.fixed_timestep
     }
 
     pub fn is_min_tick(&mut self) -> bool {
-        let frame_start = self.last_tick_start;
-        let target_frame_duration = self.min_tick_duration;
-        let elapsed = Instant::now() - frame_start;
-        //info!("target {:?} / elapsed {:?}", target_frame_duration, elapsed);
-        if elapsed < target_frame_duration {
-            false
-        } else {
-            true
+        match self.strategy {
+            FrameLimiterStrategy::Unlimited => true,
+            FrameLimiterStrategy::Sleep => self.last_tick_start.elapsed() >= self.min_tick_duration,
         }
     }
-}
\ No newline at end of file
+
+    /// Duration the game loop can sleep before the next update, fixed update or render is due
+    pub fn sleep_duration(&self) -> Duration {
+        if self.strategy == FrameLimiterStrategy::Unlimited {
+            return Duration::from_secs(0);
+        }
+        let now = Instant::now();
+        [
+            (self.last_tick_start, self.min_tick_duration),
+            (self.last_fixed_measure, self.fixed_timestep.remaining_before_next_step()),
+            (self.last_render_frame_start, self.target_render_duration),
+        ]
+        .iter()
+        .map(|(start, target)| (*start + *target).saturating_duration_since(now))
+        .min()
+        .unwrap_or_default()
+    }
+}
+
+fn rate_to_duration(name: &str, rate: u32) -> Duration {
+    assert!(rate > 0, "FrameLimiter::config parameter `{}` is {}. This parameter must be greater than zero!", name, rate);
+    Duration::from_secs(1) / rate
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::frame_limiter_config::FrameLimiterConfigBuilder;
+
+    use super::*;
+
+    #[test]
+    fn frame_limiter_rates_test() {
+        let limiter = FrameLimiter::new(
+            &FrameLimiterConfigBuilder::new().with_update_rate(100).with_fixed_update_rate(50).with_render_rate(25).get(),
+        );
+        assert_eq!(Duration::from_millis(10), limiter.min_tick_duration);
+        assert_eq!(Duration::from_millis(20), limiter.fixed_timestep.fixed_duration());
+        assert_eq!(Duration::from_millis(40), limiter.target_render_duration);
+        assert!(limiter.sleep_duration() <= Duration::from_millis(10));
+
+        let mut unlimited = FrameLimiter::new(&FrameLimiterConfigBuilder::new().unlimited().get());
+        assert!(unlimited.is_min_tick());
+        assert!(unlimited.render_unlocked());
+        assert_eq!(Duration::from_secs(0), unlimited.sleep_duration());
+    }
+
+    #[test]
+    fn fixed_timestep_accumulate_test() {
+        let mut timestep = FixedTimestep::new(Duration::from_millis(10), 3);
+        assert_eq!(0, timestep.accumulate(Duration::from_millis(4)));
+        assert_eq!(1, timestep.accumulate(Duration::from_millis(8)));
+        assert!((timestep.alpha() - 0.2).abs() < 0.0001);
+        assert_eq!(2, timestep.accumulate(Duration::from_millis(18)));
+        assert_eq!(0., timestep.alpha());
+
+        // Late by 10 steps, only 3 are executed and the late time is dropped
+        assert_eq!(3, timestep.accumulate(Duration::from_millis(105)));
+        assert!((timestep.alpha() - 0.5).abs() < 0.0001);
+    }
+}
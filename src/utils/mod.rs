@@ -1,4 +1,6 @@
 //! Utilities provided by `Scion` to help to do some basic stuff.
+use std::fmt::{Display, Formatter};
+
 pub mod file;
 pub mod logger;
 pub mod maths;
@@ -15,3 +17,11 @@ impl ScionError {
         Self{details: msg.to_string()}
     }
 }
+
+impl Display for ScionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for ScionError {}